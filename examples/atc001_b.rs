@@ -2,12 +2,6 @@ use competitive::prelude::*;
 use competitive::union_find::*;
 
 fn main() {
-    // let ss = io::read_string();
-    // let mut sc = io::Scanner::new(&ss);
-    // let n: usize = sc.next();
-    // let q: usize = sc.next();
-    // let qs: Vec<(i32, usize, usize)> = (0..q).map(|_| (sc.next(), sc.next(), sc.next())).collect();
-
     input! {
         n: usize,
         q: usize,
@@ -19,9 +13,7 @@ fn main() {
         if p == 0 {
             uf.union(a, b);
         } else {
-            let pa = uf.find(a);
-            let pb = uf.find(b);
-            println!("{}", if pa == pb { "Yes" } else { "No" });
+            println!("{}", if uf.same(a, b) { "Yes" } else { "No" });
         }
     }
 }
@@ -0,0 +1,274 @@
+use crate::gf::GF;
+
+// NTT-friendly primes used for arbitrary moduli
+const M1: u64 = 754974721; // 45 * 2^24 + 1
+const M2: u64 = 167772161; // 5 * 2^25 + 1
+const M3: u64 = 469762049; // 7 * 2^26 + 1
+
+// Below this size of the shorter input, the naive O(nm) method is faster
+const NAIVE_THRESHOLD: usize = 60;
+
+const fn pow_mod(a: u64, e: u64, p: u64) -> u64 {
+    let mut a = a % p;
+    let mut e = e;
+    let mut r = 1 % p;
+    while e > 0 {
+        if e & 1 != 0 {
+            r = r * a % p;
+        }
+        a = a * a % p;
+        e >>= 1;
+    }
+    r
+}
+
+/// Smallest primitive root modulo a prime `p`
+///
+/// This is a `const fn`, so it can be evaluated at compile time.
+///
+/// ```
+/// # use competitive::convolution::primitive_root;
+/// const G: u64 = primitive_root(998244353);
+/// assert_eq!(G, 3);
+/// assert_eq!(primitive_root(1_000_000_007), 5);
+/// ```
+///
+pub const fn primitive_root(p: u64) -> u64 {
+    if p == 2 {
+        return 1;
+    }
+
+    // distinct prime factors of p - 1
+    let mut factors = [0; 64];
+    let mut k = 0;
+    let mut x = p - 1;
+    let mut i = 2;
+    while i * i <= x {
+        if x.is_multiple_of(i) {
+            factors[k] = i;
+            k += 1;
+            while x.is_multiple_of(i) {
+                x /= i;
+            }
+        }
+        i += 1;
+    }
+    if x > 1 {
+        factors[k] = x;
+        k += 1;
+    }
+
+    let mut g = 2;
+    loop {
+        let mut ok = true;
+        let mut j = 0;
+        while j < k {
+            if pow_mod(g, (p - 1) / factors[j], p) == 1 {
+                ok = false;
+                break;
+            }
+            j += 1;
+        }
+        if ok {
+            return g;
+        }
+        g += 1;
+    }
+}
+
+struct Ntt<const P: u64>;
+
+impl<const P: u64> Ntt<P> {
+    const ROOT: u64 = primitive_root(P);
+    // NTT of length 2^k is available iff k <= RANK2
+    const RANK2: u32 = (P - 1).trailing_zeros();
+
+    // In-place NTT of length 2^k. The result is in the bit-reversed order.
+    fn butterfly(a: &mut [u64]) {
+        let n = a.len();
+        let mut len = n;
+        while len > 1 {
+            let half = len / 2;
+            let w = pow_mod(Self::ROOT, (P - 1) / len as u64, P);
+            for block in a.chunks_mut(len) {
+                let (l, r) = block.split_at_mut(half);
+                let mut wi = 1;
+                for (x, y) in l.iter_mut().zip(r.iter_mut()) {
+                    let s = *x + *y;
+                    let d = *x + P - *y;
+                    *x = if s >= P { s - P } else { s };
+                    *y = d % P * wi % P;
+                    wi = wi * w % P;
+                }
+            }
+            len = half;
+        }
+    }
+
+    // Inverse of `butterfly`, taking the bit-reversed order. Includes the division by n.
+    fn butterfly_inv(a: &mut [u64]) {
+        let n = a.len();
+        let mut len = 2;
+        while len <= n {
+            let half = len / 2;
+            let w = pow_mod(Self::ROOT, P - 1 - (P - 1) / len as u64, P);
+            for block in a.chunks_mut(len) {
+                let (l, r) = block.split_at_mut(half);
+                let mut wi = 1;
+                for (x, y) in l.iter_mut().zip(r.iter_mut()) {
+                    let t = *y * wi % P;
+                    let s = *x + t;
+                    *y = (*x + P - t) % P;
+                    *x = if s >= P { s - P } else { s };
+                    wi = wi * w % P;
+                }
+            }
+            len *= 2;
+        }
+        let inv = pow_mod(n as u64, P - 2, P);
+        for x in a.iter_mut() {
+            *x = *x * inv % P;
+        }
+    }
+
+    fn convolution(a: &[u64], b: &[u64]) -> Vec<u64> {
+        let len = a.len() + b.len() - 1;
+        let n = len.next_power_of_two();
+        assert!(n.trailing_zeros() <= Self::RANK2, "too long for NTT");
+
+        let mut fa = a.iter().map(|&x| x % P).collect::<Vec<_>>();
+        let mut fb = b.iter().map(|&x| x % P).collect::<Vec<_>>();
+        fa.resize(n, 0);
+        fb.resize(n, 0);
+        Self::butterfly(&mut fa);
+        Self::butterfly(&mut fb);
+        for (x, &y) in fa.iter_mut().zip(fb.iter()) {
+            *x = *x * y % P;
+        }
+        Self::butterfly_inv(&mut fa);
+        fa.truncate(len);
+        fa
+    }
+}
+
+fn naive<const P: u64>(a: &[u64], b: &[u64]) -> Vec<u64> {
+    let mut ret = vec![0; a.len() + b.len() - 1];
+    for (i, &x) in a.iter().enumerate() {
+        for (j, &y) in b.iter().enumerate() {
+            ret[i + j] = (ret[i + j] + x * y) % P;
+        }
+    }
+    ret
+}
+
+// Convolution modulo P through CRT of three NTT-friendly primes.
+// Exact as long as every coefficient of the true product is less than M1 * M2 * M3 (about 6 * 10^25).
+fn three_prime<const P: u64>(a: &[u64], b: &[u64]) -> Vec<u64> {
+    let c1 = Ntt::<M1>::convolution(a, b);
+    let c2 = Ntt::<M2>::convolution(a, b);
+    let c3 = Ntt::<M3>::convolution(a, b);
+
+    // Garner's algorithm
+    let m1_inv_m2 = pow_mod(M1, M2 - 2, M2);
+    let m12_inv_m3 = pow_mod(M1 * M2 % M3, M3 - 2, M3);
+    let m12_mod_p = M1 % P * (M2 % P) % P;
+    c1.iter()
+        .zip(c2.iter())
+        .zip(c3.iter())
+        .map(|((&x1, &x2), &x3)| {
+            // x = x1 + t2 * M1 + t3 * M1 * M2
+            let t2 = (x2 + M2 - x1 % M2) % M2 * m1_inv_m2 % M2;
+            let y = (x1 + t2 * M1) % M3;
+            let t3 = (x3 + M3 - y) % M3 * m12_inv_m3 % M3;
+            (x1 % P + t2 % P * (M1 % P) + t3 % P * m12_mod_p) % P
+        })
+        .collect()
+}
+
+/// Convolution over `GF<P>`
+///
+/// Returns `c` such that `c[k]` is the sum of `a[i] * b[j]` over `i + j = k`.
+/// The result is empty if either input is empty.
+///
+/// NTT is used directly when `P` is NTT-friendly for the result length (e.g. 998244353).
+/// Otherwise (e.g. 10^9 + 7) the result is restored from three NTT-friendly primes by CRT,
+/// which works for any `P` less than 2^31 and results of length up to 2^24.
+/// O((n + m) log(n + m)).
+///
+/// # Examples
+///
+/// ```
+/// # use competitive::convolution::convolution;
+/// type GF = competitive::gf::GF<998244353>;
+///
+/// let a = [1, 2, 3].iter().map(|&x| GF::new(x)).collect::<Vec<_>>();
+/// let b = [4, 5].iter().map(|&x| GF::new(x)).collect::<Vec<_>>();
+/// let c = convolution(&a, &b);
+/// assert_eq!(c.iter().map(|x| x.0).collect::<Vec<_>>(), vec![4, 13, 22, 15]);
+/// ```
+///
+pub fn convolution<const P: u64>(a: &[GF<P>], b: &[GF<P>]) -> Vec<GF<P>> {
+    if a.is_empty() || b.is_empty() {
+        return vec![];
+    }
+    let a = a.iter().map(|x| x.0).collect::<Vec<_>>();
+    let b = b.iter().map(|x| x.0).collect::<Vec<_>>();
+    let len = a.len() + b.len() - 1;
+
+    let c = if a.len().min(b.len()) <= NAIVE_THRESHOLD {
+        naive::<P>(&a, &b)
+    } else if len.next_power_of_two().trailing_zeros() <= Ntt::<P>::RANK2 {
+        Ntt::<P>::convolution(&a, &b)
+    } else {
+        three_prime::<P>(&a, &b)
+    };
+    c.into_iter().map(GF).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::random_gf;
+
+    fn raw<const P: u64>(a: &[GF<P>]) -> Vec<u64> {
+        a.iter().map(|x| x.0).collect()
+    }
+
+    fn check<const P: u64>(n: usize, m: usize, seed: &mut u64) {
+        let a = random_gf::<P>(n, seed);
+        let b = random_gf::<P>(m, seed);
+        let expected = naive::<P>(&raw(&a), &raw(&b));
+        assert_eq!(raw(&convolution(&a, &b)), expected);
+    }
+
+    #[test]
+    fn test_primitive_root() {
+        for &p in [2, 3, 7, 998244353, 1000000007, M1, M2, M3].iter() {
+            let g = primitive_root(p);
+            // g generates the group iff g^((p - 1) / q) != 1 for each prime q | p - 1
+            for (q, _) in crate::prime::factor((p - 1) as usize) {
+                assert_ne!(pow_mod(g, (p - 1) / q as u64, p), 1);
+            }
+        }
+    }
+
+    #[test]
+    fn test_convolution() {
+        let mut seed = 1;
+        for &(n, m) in [(1, 1), (3, 100), (61, 61), (100, 257), (1000, 1)].iter() {
+            check::<998244353>(n, m, &mut seed);
+            check::<1000000007>(n, m, &mut seed);
+            check::<M2>(n, m, &mut seed);
+            check::<2147483647>(n, m, &mut seed);
+        }
+        let a: Vec<GF<7>> = vec![];
+        assert!(convolution(&a, &[GF(1)]).is_empty());
+
+        let a = random_gf::<998244353>(1000, &mut seed);
+        let b = random_gf::<998244353>(3000, &mut seed);
+        assert_eq!(
+            three_prime::<998244353>(&raw(&a), &raw(&b)),
+            Ntt::<998244353>::convolution(&raw(&a), &raw(&b))
+        );
+    }
+}
@@ -0,0 +1,570 @@
+use crate::inf::MaybeInf::*;
+use num::{Bounded, Zero};
+use std::cmp::{min, Reverse};
+use std::collections::{BinaryHeap, VecDeque};
+use std::convert::TryFrom;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+/// Edge of a flow network
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FlowEdge<Cap> {
+    pub from: usize,
+    pub to: usize,
+    pub cap: Cap,
+    pub flow: Cap,
+}
+
+#[derive(Debug, Clone)]
+struct Edge<Cap> {
+    to: usize,
+    rev: usize,
+    cap: Cap,
+}
+
+/// Max flow by Dinic's algorithm
+#[derive(Debug, Clone)]
+pub struct MaxFlow<Cap> {
+    g: Vec<Vec<Edge<Cap>>>,
+    // position of each edge: (from, index in g[from])
+    pos: Vec<(usize, usize)>,
+}
+
+impl<Cap> MaxFlow<Cap>
+where
+    Cap:
+        Copy + Ord + Zero + Bounded + Add<Output = Cap> + Sub<Output = Cap> + AddAssign + SubAssign,
+{
+    /// Create a network with `n` nodes and no edges.
+    pub fn new(n: usize) -> Self {
+        Self {
+            g: (0..n).map(|_| vec![]).collect(),
+            pos: vec![],
+        }
+    }
+
+    /// Add an edge `from -> to` with capacity `cap`, and returns its id.
+    pub fn add_edge(&mut self, from: usize, to: usize, cap: Cap) -> usize {
+        assert!(cap >= Cap::zero());
+        let id = self.pos.len();
+        let from_ix = self.g[from].len();
+        let to_ix = self.g[to].len() + if from == to { 1 } else { 0 };
+        self.pos.push((from, from_ix));
+        self.g[from].push(Edge {
+            to,
+            rev: to_ix,
+            cap,
+        });
+        self.g[to].push(Edge {
+            to: from,
+            rev: from_ix,
+            cap: Cap::zero(),
+        });
+        id
+    }
+
+    /// Returns the state of the edge `id`.
+    pub fn edge(&self, id: usize) -> FlowEdge<Cap> {
+        let (from, ix) = self.pos[id];
+        let e = &self.g[from][ix];
+        let re = &self.g[e.to][e.rev];
+        FlowEdge {
+            from,
+            to: e.to,
+            cap: e.cap + re.cap,
+            flow: re.cap,
+        }
+    }
+
+    /// Returns the states of all edges in the order of ids.
+    pub fn edges(&self) -> Vec<FlowEdge<Cap>> {
+        (0..self.pos.len()).map(|i| self.edge(i)).collect()
+    }
+
+    /// O(n^2 m).
+    /// Flow as much as possible from `s` to `t`, and returns the amount.
+    pub fn flow(&mut self, s: usize, t: usize) -> Cap {
+        self.flow_with_limit(s, t, Cap::max_value())
+    }
+
+    /// O(n^2 m).
+    /// Flow at most `limit` from `s` to `t`, and returns the amount.
+    pub fn flow_with_limit(&mut self, s: usize, t: usize, limit: Cap) -> Cap {
+        assert_ne!(s, t);
+        let n = self.g.len();
+        let mut flow = Cap::zero();
+
+        while flow < limit {
+            let level = self.levels(s);
+            if level[t] == usize::MAX {
+                break;
+            }
+            let mut iter = vec![0; n];
+            loop {
+                let f = self.augment(s, t, limit - flow, &level, &mut iter);
+                if f == Cap::zero() {
+                    break;
+                }
+                flow += f;
+            }
+        }
+
+        flow
+    }
+
+    /// Returns nodes reachable from `s` in the residual network.
+    /// After `flow(s, t)`, it is the `s` side of a minimum cut.
+    pub fn min_cut(&self, s: usize) -> Vec<bool> {
+        let mut visited = vec![false; self.g.len()];
+        let mut q = VecDeque::new();
+        visited[s] = true;
+        q.push_back(s);
+        while let Some(u) = q.pop_front() {
+            for e in self.g[u].iter() {
+                if e.cap > Cap::zero() && !visited[e.to] {
+                    visited[e.to] = true;
+                    q.push_back(e.to);
+                }
+            }
+        }
+        visited
+    }
+
+    // BFS distances from `s` in the residual network
+    fn levels(&self, s: usize) -> Vec<usize> {
+        let mut level = vec![usize::MAX; self.g.len()];
+        let mut q = VecDeque::new();
+        level[s] = 0;
+        q.push_back(s);
+        while let Some(u) = q.pop_front() {
+            for e in self.g[u].iter() {
+                if e.cap > Cap::zero() && level[e.to] == usize::MAX {
+                    level[e.to] = level[u] + 1;
+                    q.push_back(e.to);
+                }
+            }
+        }
+        level
+    }
+
+    // Find an augmenting path along `level` by iterative DFS, and push flow on it
+    fn augment(
+        &mut self,
+        s: usize,
+        t: usize,
+        limit: Cap,
+        level: &[usize],
+        iter: &mut [usize],
+    ) -> Cap {
+        // nodes on the current path
+        let mut path = vec![s];
+        while let Some(&u) = path.last() {
+            if u == t {
+                let mut f = limit;
+                for &v in path[..path.len() - 1].iter() {
+                    f = min(f, self.g[v][iter[v]].cap);
+                }
+                for &v in path[..path.len() - 1].iter() {
+                    let (to, rev) = (self.g[v][iter[v]].to, self.g[v][iter[v]].rev);
+                    self.g[v][iter[v]].cap -= f;
+                    self.g[to][rev].cap += f;
+                }
+                return f;
+            }
+
+            let mut advanced = false;
+            while iter[u] < self.g[u].len() {
+                let e = &self.g[u][iter[u]];
+                if e.cap > Cap::zero() && level[u] < level[e.to] && level[e.to] <= level[t] {
+                    path.push(e.to);
+                    advanced = true;
+                    break;
+                }
+                iter[u] += 1;
+            }
+
+            if !advanced {
+                // dead end
+                path.pop();
+                if let Some(&p) = path.last() {
+                    iter[p] += 1;
+                }
+            }
+        }
+        Cap::zero()
+    }
+}
+
+/// Edge of a flow network with costs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CostFlowEdge<Cap, Cost> {
+    pub from: usize,
+    pub to: usize,
+    pub cap: Cap,
+    pub flow: Cap,
+    pub cost: Cost,
+}
+
+#[derive(Debug, Clone)]
+struct CostEdge<Cap, Cost> {
+    to: usize,
+    rev: usize,
+    cap: Cap,
+    cost: Cost,
+}
+
+/// Min cost flow by the primal-dual method
+///
+/// Shortest paths are found by Dijkstra's algorithm with potentials.
+/// Negative costs are allowed as long as there is no negative cycle,
+/// initial potentials are computed by Bellman-Ford in that case.
+#[derive(Debug, Clone)]
+pub struct MinCostFlow<Cap, Cost> {
+    g: Vec<Vec<CostEdge<Cap, Cost>>>,
+    // position of each edge: (from, index in g[from])
+    pos: Vec<(usize, usize)>,
+    // whether the residual network may have negative edges
+    negative: bool,
+}
+
+impl<Cap, Cost> MinCostFlow<Cap, Cost>
+where
+    Cap:
+        Copy + Ord + Zero + Bounded + Add<Output = Cap> + Sub<Output = Cap> + AddAssign + SubAssign,
+    Cost: Copy
+        + Ord
+        + Zero
+        + Add<Output = Cost>
+        + Sub<Output = Cost>
+        + Mul<Output = Cost>
+        + Neg<Output = Cost>
+        + TryFrom<Cap>,
+{
+    /// Create a network with `n` nodes and no edges.
+    pub fn new(n: usize) -> Self {
+        Self {
+            g: (0..n).map(|_| vec![]).collect(),
+            pos: vec![],
+            negative: false,
+        }
+    }
+
+    /// Add an edge `from -> to` with capacity `cap` and cost per unit flow `cost`,
+    /// and returns its id.
+    pub fn add_edge(&mut self, from: usize, to: usize, cap: Cap, cost: Cost) -> usize {
+        assert!(cap >= Cap::zero());
+        if cost < Cost::zero() {
+            self.negative = true;
+        }
+        let id = self.pos.len();
+        let from_ix = self.g[from].len();
+        let to_ix = self.g[to].len() + if from == to { 1 } else { 0 };
+        self.pos.push((from, from_ix));
+        self.g[from].push(CostEdge {
+            to,
+            rev: to_ix,
+            cap,
+            cost,
+        });
+        self.g[to].push(CostEdge {
+            to: from,
+            rev: from_ix,
+            cap: Cap::zero(),
+            cost: -cost,
+        });
+        id
+    }
+
+    /// Returns the state of the edge `id`.
+    pub fn edge(&self, id: usize) -> CostFlowEdge<Cap, Cost> {
+        let (from, ix) = self.pos[id];
+        let e = &self.g[from][ix];
+        let re = &self.g[e.to][e.rev];
+        CostFlowEdge {
+            from,
+            to: e.to,
+            cap: e.cap + re.cap,
+            flow: re.cap,
+            cost: e.cost,
+        }
+    }
+
+    /// Returns the states of all edges in the order of ids.
+    pub fn edges(&self) -> Vec<CostFlowEdge<Cap, Cost>> {
+        (0..self.pos.len()).map(|i| self.edge(i)).collect()
+    }
+
+    /// O(F (n + m) log n), where F is the amount of flow.
+    /// Flow at most `limit` from `s` to `t` with the minimum cost.
+    /// Returns the amount of flow and its cost.
+    pub fn flow(&mut self, s: usize, t: usize, limit: Cap) -> (Cap, Cost) {
+        *self.slope_with_limit(s, t, limit).last().unwrap()
+    }
+
+    /// Returns the minimum cost as a function of the amount of flow from `s` to `t`.
+    ///
+    /// The function is piecewise linear and convex. The result is the list of its
+    /// breakpoints `(flow, cost)`, which begins with `(0, 0)` and ends with the max flow.
+    pub fn slope(&mut self, s: usize, t: usize) -> Vec<(Cap, Cost)> {
+        self.slope_with_limit(s, t, Cap::max_value())
+    }
+
+    fn slope_with_limit(&mut self, s: usize, t: usize, limit: Cap) -> Vec<(Cap, Cost)> {
+        assert_ne!(s, t);
+        let n = self.g.len();
+        let mut h = if self.negative {
+            self.bellman_ford(s)
+        } else {
+            vec![Cost::zero(); n]
+        };
+        // The residual network has negative edges after flowing
+        self.negative = true;
+
+        let mut flow = Cap::zero();
+        let mut cost = Cost::zero();
+        let mut ret = vec![(flow, cost)];
+        let mut prev_unit: Option<Cost> = None;
+
+        while flow < limit {
+            let prev = match self.dijkstra(s, t, &mut h) {
+                Some(prev) => prev,
+                None => break,
+            };
+
+            let mut f = limit - flow;
+            let mut v = t;
+            while v != s {
+                let (u, ix) = prev[v].unwrap();
+                f = min(f, self.g[u][ix].cap);
+                v = u;
+            }
+            let mut v = t;
+            while v != s {
+                let (u, ix) = prev[v].unwrap();
+                let rev = self.g[u][ix].rev;
+                self.g[u][ix].cap -= f;
+                self.g[v][rev].cap += f;
+                v = u;
+            }
+
+            let unit = h[t] - h[s];
+            flow += f;
+            // panics if the amount of flow does not fit in `Cost`
+            cost = cost + Cost::try_from(f).ok().unwrap() * unit;
+            if prev_unit == Some(unit) {
+                ret.pop();
+            }
+            ret.push((flow, cost));
+            prev_unit = Some(unit);
+        }
+
+        ret
+    }
+
+    // Shortest paths by reduced costs `cost(u, v) + h[u] - h[v]`.
+    // Updates potentials `h` and returns the predecessor edges if `t` is reachable.
+    fn dijkstra(&self, s: usize, t: usize, h: &mut [Cost]) -> Option<Vec<Option<(usize, usize)>>> {
+        let n = self.g.len();
+        let mut dist = vec![Inf; n];
+        let mut prev = vec![None; n];
+        let mut q = BinaryHeap::new();
+        dist[s] = NonInf(Cost::zero());
+        q.push(Reverse((Cost::zero(), s)));
+
+        while let Some(Reverse((d, u))) = q.pop() {
+            if dist[u] < NonInf(d) {
+                continue;
+            }
+            for (ix, e) in self.g[u].iter().enumerate() {
+                if e.cap == Cap::zero() {
+                    continue;
+                }
+                let nd = d + e.cost + h[u] - h[e.to];
+                if NonInf(nd) < dist[e.to] {
+                    dist[e.to] = NonInf(nd);
+                    prev[e.to] = Some((u, ix));
+                    q.push(Reverse((nd, e.to)));
+                }
+            }
+        }
+
+        if dist[t] == Inf {
+            return None;
+        }
+        for (h, d) in h.iter_mut().zip(dist) {
+            if let NonInf(d) = d {
+                *h = *h + d;
+            }
+        }
+        Some(prev)
+    }
+
+    // Distances from `s` in the residual network, 0 for unreachable nodes
+    fn bellman_ford(&self, s: usize) -> Vec<Cost> {
+        let n = self.g.len();
+        let mut dist = vec![Inf; n];
+        dist[s] = NonInf(Cost::zero());
+        for _ in 0..n {
+            let mut updated = false;
+            for u in 0..n {
+                if let NonInf(d) = dist[u] {
+                    for e in self.g[u].iter() {
+                        if e.cap > Cap::zero() && NonInf(d + e.cost) < dist[e.to] {
+                            dist[e.to] = NonInf(d + e.cost);
+                            updated = true;
+                        }
+                    }
+                }
+            }
+            if !updated {
+                break;
+            }
+        }
+        dist.into_iter()
+            .map(|d| d.unwrap_or(Cost::zero()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_max_flow() {
+        let mut mf = MaxFlow::<i64>::new(6);
+        let es = [
+            mf.add_edge(0, 1, 10),
+            mf.add_edge(0, 2, 10),
+            mf.add_edge(1, 2, 2),
+            mf.add_edge(1, 3, 4),
+            mf.add_edge(1, 4, 8),
+            mf.add_edge(2, 4, 9),
+            mf.add_edge(4, 3, 6),
+            mf.add_edge(3, 5, 10),
+            mf.add_edge(4, 5, 10),
+        ];
+        assert_eq!(mf.flow(0, 5), 19);
+
+        // flow conservation
+        let mut balance = [0_i64; 6];
+        for &id in es.iter() {
+            let e = mf.edge(id);
+            assert!(0 <= e.flow && e.flow <= e.cap);
+            balance[e.from] -= e.flow;
+            balance[e.to] += e.flow;
+        }
+        assert_eq!(balance, [-19, 0, 0, 0, 0, 19]);
+
+        let cut = mf.min_cut(0);
+        let cut_cap: i64 = mf
+            .edges()
+            .iter()
+            .filter(|e| cut[e.from] && !cut[e.to])
+            .map(|e| e.cap)
+            .sum();
+        assert_eq!(cut_cap, 19);
+        assert!(cut[0] && !cut[5]);
+    }
+
+    #[test]
+    fn test_flow_with_limit() {
+        let mut mf = MaxFlow::<u64>::new(4);
+        mf.add_edge(0, 1, 3);
+        mf.add_edge(0, 2, 2);
+        mf.add_edge(1, 3, 2);
+        mf.add_edge(2, 3, 3);
+        mf.add_edge(1, 2, 1);
+        let e = mf.add_edge(3, 3, 5);
+        assert_eq!(mf.flow_with_limit(0, 3, 2), 2);
+        assert_eq!(mf.flow(0, 3), 3);
+        assert_eq!(mf.flow(0, 3), 0);
+        assert_eq!(
+            mf.edge(e),
+            FlowEdge {
+                from: 3,
+                to: 3,
+                cap: 5,
+                flow: 0
+            }
+        );
+    }
+
+    #[test]
+    fn test_long_path() {
+        let n = 100_000;
+        let mut mf = MaxFlow::<i64>::new(n);
+        for i in 1..n {
+            mf.add_edge(i - 1, i, 1_000_000_000_000);
+        }
+        mf.add_edge(0, n - 1, 1);
+        assert_eq!(mf.flow(0, n - 1), 1_000_000_000_001);
+    }
+
+    #[test]
+    fn test_min_cost_flow() {
+        let mut mcf = MinCostFlow::<i64, i64>::new(4);
+        mcf.add_edge(0, 1, 2, 1);
+        mcf.add_edge(0, 2, 1, 2);
+        mcf.add_edge(1, 2, 1, 1);
+        mcf.add_edge(1, 3, 1, 3);
+        mcf.add_edge(2, 3, 2, 1);
+        assert_eq!(mcf.flow(0, 3, 2), (2, 6));
+
+        let mut mcf = MinCostFlow::<i64, i64>::new(4);
+        mcf.add_edge(0, 1, 2, 1);
+        mcf.add_edge(0, 2, 1, 2);
+        mcf.add_edge(1, 2, 1, 1);
+        mcf.add_edge(1, 3, 1, 3);
+        mcf.add_edge(2, 3, 2, 1);
+        assert_eq!(mcf.slope(0, 3), vec![(0, 0), (2, 6), (3, 10)]);
+        let total: i64 = mcf.edges().iter().map(|e| e.flow * e.cost).sum();
+        assert_eq!(total, 10);
+
+        // unsigned capacities with signed costs
+        let mut mcf = MinCostFlow::<u64, i64>::new(3);
+        mcf.add_edge(0, 1, 5, -2);
+        mcf.add_edge(1, 2, 3, 4);
+        mcf.add_edge(0, 2, 10, 3);
+        assert_eq!(mcf.flow(0, 2, 4), (4, 9));
+    }
+
+    #[test]
+    fn test_assignment() {
+        // assign 3 workers to 3 jobs minimizing the total cost
+        let cost = [[4, 1, 3], [2, 0, 5], [3, 2, 2]];
+        let mut mcf = MinCostFlow::<i64, i64>::new(8);
+        let (s, t) = (6, 7);
+        for (i, row) in cost.iter().enumerate() {
+            mcf.add_edge(s, i, 1, 0);
+            mcf.add_edge(3 + i, t, 1, 0);
+            for (j, &c) in row.iter().enumerate() {
+                mcf.add_edge(i, 3 + j, 1, c);
+            }
+        }
+        assert_eq!(mcf.flow(s, t, 3), (3, 5));
+
+        // maximize the total profit by negating costs
+        let mut mcf = MinCostFlow::<i64, i64>::new(8);
+        for (i, row) in cost.iter().enumerate() {
+            mcf.add_edge(s, i, 1, 0);
+            mcf.add_edge(3 + i, t, 1, 0);
+            for (j, &c) in row.iter().enumerate() {
+                mcf.add_edge(i, 3 + j, 1, -c);
+            }
+        }
+        assert_eq!(mcf.flow(s, t, 3), (3, -11));
+    }
+
+    #[test]
+    fn test_negative_costs() {
+        let mut mcf = MinCostFlow::<i64, i64>::new(5);
+        mcf.add_edge(0, 1, 2, -3);
+        mcf.add_edge(1, 4, 1, 2);
+        mcf.add_edge(1, 2, 2, -1);
+        mcf.add_edge(2, 4, 2, 4);
+        mcf.add_edge(0, 3, 1, 5);
+        mcf.add_edge(3, 4, 1, -7);
+        assert_eq!(mcf.slope(0, 4), vec![(0, 0), (1, -2), (2, -3), (3, -3)]);
+        assert_eq!(mcf.flow(0, 4, 10), (0, 0));
+    }
+}
@@ -0,0 +1,432 @@
+use crate::convolution::convolution;
+use crate::gf::GF;
+use std::ops::{Add, Mul, Neg, Sub};
+
+/// Formal power series over `GF<P>`
+///
+/// `self.0[i]` is the coefficient of `x^i`. Operations which take `n` compute the result modulo `x^n`.
+/// Newton's method is used for `inv`, `log`, `exp`, `pow` and `sqrt`,
+/// so they run in O(n log n) when `P` is NTT-friendly like 998244353.
+///
+/// ```
+/// use competitive::fps::Fps;
+/// type GF = competitive::gf::GF<998244353>;
+///
+/// // 1 / (1 - x - x^2) generates Fibonacci numbers
+/// let f = Fps::from(vec![GF::new(1), -GF::new(1), -GF::new(1)]);
+/// let fib = f.inv(8);
+/// assert_eq!(fib, Fps::from(vec![1, 1, 2, 3, 5, 8, 13, 21]));
+/// ```
+///
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct Fps<const P: u64>(pub Vec<GF<P>>);
+
+impl<const P: u64, T: Into<GF<P>>> From<Vec<T>> for Fps<P> {
+    fn from(v: Vec<T>) -> Self {
+        Self(v.into_iter().map(Into::into).collect())
+    }
+}
+
+impl<const P: u64> Fps<P> {
+    /// Number of coefficients.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns true if there are no coefficients.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Coefficient of `x^i`. Zero for `i >= len()`.
+    pub fn coef(&self, i: usize) -> GF<P> {
+        self.0.get(i).copied().unwrap_or(GF(0))
+    }
+
+    /// First `n` coefficients, padded with zeros. i.e. `self` mod `x^n`.
+    pub fn prefix(&self, n: usize) -> Self {
+        let mut v = self.0[..n.min(self.len())].to_vec();
+        v.resize(n, GF(0));
+        Self(v)
+    }
+
+    /// Remove trailing zeros.
+    pub fn normalize(mut self) -> Self {
+        while self.0.last() == Some(&GF(0)) {
+            self.0.pop();
+        }
+        self
+    }
+
+    fn reversed(&self) -> Self {
+        Self(self.0.iter().rev().copied().collect())
+    }
+
+    fn scale(&self, c: GF<P>) -> Self {
+        Self(self.0.iter().map(|&x| x * c).collect())
+    }
+
+    /// O(n).
+    /// Value at `x` by Horner's method.
+    pub fn eval(&self, x: GF<P>) -> GF<P> {
+        self.0.iter().rev().fold(GF(0), |acc, &c| acc * x + c)
+    }
+
+    /// O(n).
+    /// Formal derivative.
+    pub fn derivative(&self) -> Self {
+        Self(
+            self.0
+                .iter()
+                .enumerate()
+                .skip(1)
+                .map(|(i, &c)| c * i)
+                .collect(),
+        )
+    }
+
+    /// O(n).
+    /// Formal integral with the constant term 0.
+    pub fn integral(&self) -> Self {
+        let n = self.len();
+        // inv[i] = 1 / i
+        let mut inv = vec![GF(1); n + 1];
+        for i in 2..=n {
+            inv[i] = -inv[P as usize % i] * (P as usize / i);
+        }
+        let mut v = Vec::with_capacity(n + 1);
+        v.push(GF(0));
+        v.extend(self.0.iter().enumerate().map(|(i, &c)| c * inv[i + 1]));
+        Self(v)
+    }
+
+    /// O(n log n).
+    /// `1 / self` mod `x^n`. The constant term must be non-zero.
+    pub fn inv(&self, n: usize) -> Self {
+        assert_ne!(self.coef(0), GF(0), "constant term must be non-zero");
+        let mut g = Self(vec![self.coef(0).recip()]);
+        let mut k = 1;
+        while k < n {
+            k *= 2;
+            // g <- g (2 - f g)
+            let mut t = -(&self.prefix(k) * &g).prefix(k);
+            t.0[0] += 2;
+            g = (&g * &t).prefix(k);
+        }
+        g.prefix(n)
+    }
+
+    /// O(n log n).
+    /// `log(self)` mod `x^n`. The constant term must be 1.
+    pub fn log(&self, n: usize) -> Self {
+        assert_eq!(self.coef(0), GF(1), "constant term must be 1");
+        if n == 0 {
+            return Self(vec![]);
+        }
+        let f = self.prefix(n);
+        (&f.derivative() * &f.inv(n - 1)).prefix(n - 1).integral()
+    }
+
+    /// O(n log n).
+    /// `exp(self)` mod `x^n`. The constant term must be 0.
+    pub fn exp(&self, n: usize) -> Self {
+        assert_eq!(self.coef(0), GF(0), "constant term must be 0");
+        let mut g = Self(vec![GF(1)]);
+        let mut k = 1;
+        while k < n {
+            k *= 2;
+            // g <- g (1 - log(g) + f)
+            let mut t = &self.prefix(k) - &g.log(k);
+            t.0[0] += 1;
+            g = (&g * &t).prefix(k);
+        }
+        g.prefix(n)
+    }
+
+    /// O(n log n).
+    /// `self^k` mod `x^n`.
+    pub fn pow(&self, k: u64, n: usize) -> Self {
+        if k == 0 {
+            return Self(vec![GF(1)]).prefix(n);
+        }
+        let i = match self.0.iter().position(|&c| c != GF(0)) {
+            Some(i) => i,
+            None => return Self(vec![]).prefix(n),
+        };
+        // the result is divisible by x^(i k)
+        if i > 0 && k >= n.div_ceil(i) as u64 {
+            return Self(vec![]).prefix(n);
+        }
+        let shift = i * k as usize;
+        let c = self.0[i];
+        let h = Self(self.0[i..].to_vec()).scale(c.recip());
+        let g = h.log(n - shift).scale(GF::new(k % P)).exp(n - shift);
+        let mut v = vec![GF(0); shift];
+        v.extend(g.scale(c.pow(k)).0);
+        Self(v)
+    }
+
+    /// O(n log n).
+    /// A square root of `self` mod `x^n` if exists.
+    pub fn sqrt(&self, n: usize) -> Option<Self> {
+        let i = match self.0.iter().position(|&c| c != GF(0)) {
+            Some(i) => i,
+            None => return Some(Self(vec![]).prefix(n)),
+        };
+        if i & 1 != 0 {
+            return None;
+        }
+        let shift = i / 2;
+        if shift >= n {
+            return Some(Self(vec![]).prefix(n));
+        }
+
+        let h = Self(self.0[i..].to_vec());
+        let m = n - shift;
+        let mut g = Self(vec![h.0[0].sqrt()?]);
+        let half = GF::new(2).recip();
+        let mut k = 1;
+        while k < m {
+            k *= 2;
+            // g <- (g + h / g) / 2
+            g = (&g + &(&h.prefix(k) * &g.inv(k)).prefix(k)).scale(half);
+        }
+        let mut v = vec![GF(0); shift];
+        v.extend(g.prefix(m).0);
+        Some(Self(v))
+    }
+
+    /// O(n log n).
+    /// Polynomial division. Returns the quotient and the remainder, both normalized.
+    pub fn div_rem(&self, g: &Self) -> (Self, Self) {
+        let f = self.clone().normalize();
+        let g = g.clone().normalize();
+        assert!(!g.is_empty(), "division by zero");
+        if f.len() < g.len() {
+            return (Self(vec![]), f);
+        }
+
+        let k = f.len() - g.len() + 1;
+        let q = (&f.reversed().prefix(k) * &g.reversed().inv(k))
+            .prefix(k)
+            .reversed();
+        let r = (&f - &(&q * &g)).prefix(g.len() - 1);
+        (q.normalize(), r.normalize())
+    }
+
+    // tree[k] = prod of (x - xs[i]) over leaves i under the node k (1 for padding)
+    fn subproduct_tree(xs: &[GF<P>]) -> Vec<Self> {
+        let sz = xs.len().next_power_of_two();
+        let mut tree = vec![Self(vec![GF(1)]); 2 * sz];
+        for (i, &x) in xs.iter().enumerate() {
+            tree[sz + i] = Self(vec![-x, GF(1)]);
+        }
+        for k in (1..sz).rev() {
+            tree[k] = &tree[2 * k] * &tree[2 * k + 1];
+        }
+        tree
+    }
+
+    fn eval_with_tree(&self, xs: &[GF<P>], tree: &[Self]) -> Vec<GF<P>> {
+        let sz = tree.len() / 2;
+        let mut rem = vec![Self(vec![]); 2 * sz];
+        rem[1] = self.div_rem(&tree[1]).1;
+        for k in 2..sz + xs.len() {
+            rem[k] = rem[k / 2].div_rem(&tree[k]).1;
+        }
+        (0..xs.len()).map(|i| rem[sz + i].coef(0)).collect()
+    }
+
+    /// O(n log^2 n).
+    /// Values at each point of `xs`.
+    pub fn multipoint_eval(&self, xs: &[GF<P>]) -> Vec<GF<P>> {
+        if xs.is_empty() {
+            return vec![];
+        }
+        self.eval_with_tree(xs, &Self::subproduct_tree(xs))
+    }
+
+    /// O(n log^2 n).
+    /// The polynomial of the least degree which takes `ys[i]` at `xs[i]`.
+    /// `xs` must be distinct.
+    pub fn interpolate(xs: &[GF<P>], ys: &[GF<P>]) -> Self {
+        assert_eq!(xs.len(), ys.len());
+        if xs.is_empty() {
+            return Self(vec![]);
+        }
+        let tree = Self::subproduct_tree(xs);
+        let sz = tree.len() / 2;
+        let d = tree[1].derivative().eval_with_tree(xs, &tree);
+
+        // sum of ys[i] / d[i] * prod of (x - xs[j]) over j != i, merged bottom-up
+        let mut node = vec![Self(vec![]); 2 * sz];
+        for i in 0..xs.len() {
+            node[sz + i] = Self(vec![ys[i] / d[i]]);
+        }
+        for k in (1..sz).rev() {
+            node[k] = &(&node[2 * k] * &tree[2 * k + 1]) + &(&node[2 * k + 1] * &tree[2 * k]);
+        }
+        node.swap_remove(1).normalize()
+    }
+}
+
+impl<const P: u64> Add for &Fps<P> {
+    type Output = Fps<P>;
+    fn add(self, rhs: Self) -> Fps<P> {
+        let n = self.len().max(rhs.len());
+        Fps((0..n).map(|i| self.coef(i) + rhs.coef(i)).collect())
+    }
+}
+
+impl<const P: u64> Sub for &Fps<P> {
+    type Output = Fps<P>;
+    fn sub(self, rhs: Self) -> Fps<P> {
+        let n = self.len().max(rhs.len());
+        Fps((0..n).map(|i| self.coef(i) - rhs.coef(i)).collect())
+    }
+}
+
+impl<const P: u64> Mul for &Fps<P> {
+    type Output = Fps<P>;
+    fn mul(self, rhs: Self) -> Fps<P> {
+        Fps(convolution(&self.0, &rhs.0))
+    }
+}
+
+impl<const P: u64> Add for Fps<P> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        &self + &rhs
+    }
+}
+
+impl<const P: u64> Sub for Fps<P> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        &self - &rhs
+    }
+}
+
+impl<const P: u64> Mul for Fps<P> {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        &self * &rhs
+    }
+}
+
+impl<const P: u64> Neg for Fps<P> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self(self.0.into_iter().map(Neg::neg).collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::random_gf;
+
+    const P: u64 = 998244353;
+    type F = Fps<P>;
+
+    fn random(n: usize, seed: &mut u64) -> F {
+        Fps(random_gf(n, seed))
+    }
+
+    #[test]
+    fn test_arith() {
+        let f = F::from(vec![1, 2, 3]);
+        let g = F::from(vec![4, 5]);
+        assert_eq!(&f + &g, F::from(vec![5, 7, 3]));
+        assert_eq!(f.clone() - g.clone(), F::from(vec![-3, -3, 3]));
+        assert_eq!(f.clone() * g.clone(), F::from(vec![4, 13, 22, 15]));
+        assert_eq!(-g, F::from(vec![-4, -5]));
+        assert_eq!(f.eval(GF(2)), GF(17));
+        assert_eq!(f.derivative(), F::from(vec![2, 6]));
+        assert_eq!(f.integral().derivative(), f);
+        assert_eq!(f.prefix(5), F::from(vec![1, 2, 3, 0, 0]));
+        assert_eq!(f.prefix(5).normalize(), f);
+    }
+
+    #[test]
+    fn test_newton() {
+        let mut seed = 1;
+        for &n in [1, 2, 7, 64, 300].iter() {
+            let mut f = random(n, &mut seed);
+            f.0[0] = GF(1);
+
+            let g = f.inv(n);
+            assert_eq!((&f * &g).prefix(n), F::from(vec![1]).prefix(n));
+
+            let l = f.log(n);
+            assert_eq!(l.len(), n);
+            assert_eq!(l.exp(n), f);
+
+            let mut h = f.clone();
+            h.0[0] = GF(0);
+            assert_eq!(h.exp(n).log(n), h);
+
+            // leading zeros
+            let mut f = f.clone();
+            f.0[0] = GF(0);
+            if n > 1 {
+                f.0[1] = GF(0);
+            }
+            let mut naive = F::from(vec![1]);
+            for k in 0..5 {
+                assert_eq!(f.pow(k, n), naive.prefix(n));
+                naive = (&naive * &f).prefix(n);
+            }
+            assert_eq!(f.pow(1 << 40, n), F::from(vec![0]).prefix(n));
+
+            let sq = (&f * &f).prefix(n);
+            let r = sq.sqrt(n).unwrap();
+            assert_eq!((&r * &r).prefix(n), sq);
+        }
+
+        // exp(x) = sum of x^k / k!
+        let e = F::from(vec![0, 1]).exp(6);
+        let fact = [1, 1, 2, 6, 24, 120];
+        for (k, &c) in e.0.iter().enumerate() {
+            assert_eq!(c * fact[k], GF(1));
+        }
+
+        assert_eq!(F::from(vec![0, 1]).sqrt(4), None);
+        // 3 is not a quadratic residue modulo 998244353
+        assert_eq!(F::from(vec![3, 1]).sqrt(4), None);
+        let r = F::from(vec![0, 0, 4]).sqrt(3).unwrap();
+        assert!(r == F::from(vec![0, 2, 0]) || r == F::from(vec![0, -2, 0]));
+    }
+
+    #[test]
+    fn test_div_rem() {
+        let mut seed = 2;
+        for &(n, m) in [(1, 1), (5, 3), (3, 5), (100, 1), (200, 70)].iter() {
+            let f = random(n, &mut seed);
+            let g = random(m, &mut seed);
+            let (q, r) = f.div_rem(&g);
+            assert!(r.len() < g.clone().normalize().len());
+            assert_eq!((&(&q * &g) + &r).normalize(), f.clone().normalize());
+        }
+        let (q, r) = F::from(vec![-1, 0, 1]).div_rem(&F::from(vec![1, 1]));
+        assert_eq!(q, F::from(vec![-1, 1]));
+        assert!(r.is_empty());
+    }
+
+    #[test]
+    fn test_multipoint() {
+        let mut seed = 3;
+        for &(n, m) in [(1, 1), (5, 3), (10, 10), (100, 130)].iter() {
+            let f = random(n, &mut seed).normalize();
+            let xs = (0..m).map(|i| GF::new(i * i + 1)).collect::<Vec<_>>();
+            let ys = f.multipoint_eval(&xs);
+            let expected = xs.iter().map(|&x| f.eval(x)).collect::<Vec<_>>();
+            assert_eq!(ys, expected);
+            if m >= n {
+                assert_eq!(F::interpolate(&xs, &ys), f);
+            }
+        }
+        assert!(F::from(vec![1]).multipoint_eval(&[]).is_empty());
+        assert!(F::interpolate(&[], &[]).is_empty());
+    }
+}
@@ -1,3 +1,4 @@
+use num::{One, Zero};
 use std::convert::{From, Into, TryInto};
 use std::fmt::Display;
 use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
@@ -50,6 +51,46 @@ impl<const P: u64> GF<P> {
     pub fn recip(self) -> Self {
         self.pow(P - 2)
     }
+
+    /// Square root by Tonelli-Shanks algorithm
+    ///
+    /// Returns one of the square roots if exists. This requires P is prime.
+    pub fn sqrt(self) -> Option<Self> {
+        if self.0 == 0 || P == 2 {
+            return Some(self);
+        }
+        if self.pow((P - 1) / 2).0 != 1 {
+            return None;
+        }
+
+        // P - 1 = q * 2^s
+        let s = (P - 1).trailing_zeros();
+        let q = (P - 1) >> s;
+        let mut z = Self::new(2);
+        while z.pow((P - 1) / 2).0 == 1 {
+            z += 1;
+        }
+
+        let mut m = s;
+        let mut c = z.pow(q);
+        let mut t = self.pow(q);
+        let mut r = self.pow(q.div_ceil(2));
+        while t.0 != 1 {
+            // the least i such that t^(2^i) = 1
+            let mut i = 0;
+            let mut t2 = t;
+            while t2.0 != 1 {
+                t2 = t2 * t2;
+                i += 1;
+            }
+            let b = c.pow(1 << (m - i - 1));
+            m = i;
+            c = b * b;
+            t *= c;
+            r *= b;
+        }
+        Some(r)
+    }
 }
 
 impl<T: Into<GF<P>>, const P: u64> Add<T> for GF<P> {
@@ -154,6 +195,22 @@ impl<const P: u64> Neg for GF<P> {
     }
 }
 
+impl<const P: u64> Zero for GF<P> {
+    fn zero() -> Self {
+        Self(0)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl<const P: u64> One for GF<P> {
+    fn one() -> Self {
+        Self(1 % P)
+    }
+}
+
 impl<T: TryInto<i64>, const P: u64> From<T> for GF<P> {
     fn from(v: T) -> Self {
         Self::new(v)
@@ -196,6 +253,20 @@ mod test {
         let y: GF = 87654321.into();
         assert_eq!(y * x * x.recip(), y);
 
-        assert_eq!(GF::new(2).pow(50).0, (1 << 50) % 1000000007)
+        assert_eq!(GF::new(2).pow(50).0, (1 << 50) % 1000000007);
+
+        // square roots
+        for x in 0..1000 {
+            let x = GF::new(x);
+            match x.sqrt() {
+                Some(r) => assert_eq!(r * r, x),
+                None => assert_eq!(x.pow((1000000007 - 1) / 2).0, 1000000007 - 1),
+            }
+        }
+        for x in 0..1000 {
+            let x = super::GF::<998244353>::new(x);
+            assert_eq!((x * x).sqrt().map(|r| r * r), Some(x * x));
+        }
+        assert_eq!(super::GF::<998244353>::new(3).sqrt(), None);
     }
 }
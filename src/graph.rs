@@ -1,9 +1,14 @@
 use crate::inf::MaybeInf::{self, *};
+use crate::ix::{Board, Ix2};
+use crate::union_find::UnionFind;
+use num::Zero;
 use proconio::marker::Usize1;
 use proconio::source::{Readable, Source};
-use std::collections::VecDeque;
+use std::cmp::{max, min, Reverse};
+use std::collections::{BinaryHeap, VecDeque};
 use std::io::BufRead;
 use std::marker::PhantomData;
+use std::ops::Add;
 
 /// Graph trait
 pub trait Graph<'a> {
@@ -82,6 +87,18 @@ impl<IndexType: Readable<Output = usize>> Readable for MatGraph<IndexType> {
     }
 }
 
+/// Read marker for undirected tree
+///
+/// The result type is `Vec<Vec<usize>>`
+///
+/// It reads input like below:
+///
+/// ```ignore
+/// n:usize
+/// u_1:IndexType v_1:IndexType
+/// ...
+/// u_{n-1}:IndexType v_{n-1}:IndexType
+/// ```
 pub struct ListTree<IndexType = Usize1>(PhantomData<IndexType>);
 
 impl<IndexType: Readable<Output = usize>> Readable for ListTree<IndexType> {
@@ -102,9 +119,175 @@ impl<IndexType: Readable<Output = usize>> Readable for ListTree<IndexType> {
     }
 }
 
-// TODO: Markers for Directed Graph
-// TODO: Markers for Weighted Graph
-// TODO: Markers for Tree described by nth-nodes' parent
+/// Read marker for directed adjacency list graph
+///
+/// The result type is `Vec<Vec<usize>>`
+///
+/// It reads input like below:
+///
+/// ```ignore
+/// n:usize m:usize
+/// u_1:IndexType v_1:IndexType
+/// ...
+/// u_m:IndexType v_m:IndexType
+/// ```
+pub struct ListDiGraph<IndexType = Usize1>(PhantomData<IndexType>);
+
+impl<IndexType: Readable<Output = usize>> Readable for ListDiGraph<IndexType> {
+    type Output = Vec<Vec<usize>>;
+
+    fn read<R: BufRead, S: Source<R>>(source: &mut S) -> Self::Output {
+        let n = usize::read(source);
+        let m = usize::read(source);
+        let mut g = vec![vec![]; n];
+
+        for _ in 0..m {
+            let u = IndexType::read(source);
+            let v = IndexType::read(source);
+            g[u].push(v);
+        }
+
+        g
+    }
+}
+
+/// Read marker for undirected weighted adjacency list graph
+///
+/// The result type is `Vec<Vec<(usize, W::Output)>>`
+///
+/// It reads input like below:
+///
+/// ```ignore
+/// n:usize m:usize
+/// u_1:IndexType v_1:IndexType w_1:W
+/// ...
+/// u_m:IndexType v_m:IndexType w_m:W
+/// ```
+///
+/// `input!` can not parse `WeightedListGraph<IndexType, W>` directly, so use a type alias for it.
+pub struct WeightedListGraph<IndexType = Usize1, W = i64>(PhantomData<(IndexType, W)>);
+
+impl<IndexType: Readable<Output = usize>, W: Readable> Readable for WeightedListGraph<IndexType, W>
+where
+    W::Output: Clone,
+{
+    type Output = Vec<Vec<(usize, W::Output)>>;
+
+    fn read<R: BufRead, S: Source<R>>(source: &mut S) -> Self::Output {
+        let n = usize::read(source);
+        let m = usize::read(source);
+        let mut g = vec![vec![]; n];
+
+        for _ in 0..m {
+            let u = IndexType::read(source);
+            let v = IndexType::read(source);
+            let w = W::read(source);
+            g[u].push((v, w.clone()));
+            g[v].push((u, w));
+        }
+
+        g
+    }
+}
+
+/// Read marker for directed weighted adjacency list graph
+///
+/// The result type is `Vec<Vec<(usize, W::Output)>>`
+///
+/// It reads input like below:
+///
+/// ```ignore
+/// n:usize m:usize
+/// u_1:IndexType v_1:IndexType w_1:W
+/// ...
+/// u_m:IndexType v_m:IndexType w_m:W
+/// ```
+///
+/// `input!` can not parse `WeightedListDiGraph<IndexType, W>` directly, so use a type alias for it.
+pub struct WeightedListDiGraph<IndexType = Usize1, W = i64>(PhantomData<(IndexType, W)>);
+
+impl<IndexType: Readable<Output = usize>, W: Readable> Readable
+    for WeightedListDiGraph<IndexType, W>
+{
+    type Output = Vec<Vec<(usize, W::Output)>>;
+
+    fn read<R: BufRead, S: Source<R>>(source: &mut S) -> Self::Output {
+        let n = usize::read(source);
+        let m = usize::read(source);
+        let mut g: Self::Output = (0..n).map(|_| vec![]).collect();
+
+        for _ in 0..m {
+            let u = IndexType::read(source);
+            let v = IndexType::read(source);
+            let w = W::read(source);
+            g[u].push((v, w));
+        }
+
+        g
+    }
+}
+
+/// Read marker for tree described by parents of nodes
+///
+/// The result type is `Vec<Vec<usize>>`, which is undirected and rooted at node 0
+///
+/// It reads input like below, where `p_i` is the parent of node `i`:
+///
+/// ```ignore
+/// n:usize
+/// p_1:IndexType p_2:IndexType ... p_{n-1}:IndexType
+/// ```
+pub struct ParentTree<IndexType = Usize1>(PhantomData<IndexType>);
+
+impl<IndexType: Readable<Output = usize>> Readable for ParentTree<IndexType> {
+    type Output = Vec<Vec<usize>>;
+
+    fn read<R: BufRead, S: Source<R>>(source: &mut S) -> Self::Output {
+        let n = usize::read(source);
+        let mut g = vec![vec![]; n];
+
+        for v in 1..n {
+            let p = IndexType::read(source);
+            g[p].push(v);
+            g[v].push(p);
+        }
+
+        g
+    }
+}
+
+/// Read marker for edge list
+///
+/// The result type is `(usize, Vec<(usize, usize)>)`, the number of nodes and edges.
+/// The index of each edge is its id.
+///
+/// It reads input like below:
+///
+/// ```ignore
+/// n:usize m:usize
+/// u_1:IndexType v_1:IndexType
+/// ...
+/// u_m:IndexType v_m:IndexType
+/// ```
+pub struct EdgeList<IndexType = Usize1>(PhantomData<IndexType>);
+
+impl<IndexType: Readable<Output = usize>> Readable for EdgeList<IndexType> {
+    type Output = (usize, Vec<(usize, usize)>);
+
+    fn read<R: BufRead, S: Source<R>>(source: &mut S) -> Self::Output {
+        let n = usize::read(source);
+        let m = usize::read(source);
+        let edges = (0..m)
+            .map(|_| {
+                let u = IndexType::read(source);
+                let v = IndexType::read(source);
+                (u, v)
+            })
+            .collect();
+
+        (n, edges)
+    }
+}
 
 //-----
 
@@ -168,36 +351,60 @@ pub fn make_weighted_undirected_graph<W: Clone>(
     g
 }
 
-// struct Map2DGraph<C> {
-//     bd: Vec<Vec<C>>,
-// }
-
-// impl<'a, T> Graph<'a> for Map2DGraph<T> {
-//     type NodeId = (usize, usize);
-//     type Iter = std::slice::Iter<'a, Self::NodeId>;
+/// Grid graph on `Board`
+///
+/// Nodes are cells of the board, and adjacent cells are connected
+/// if both of them satisfy `passable`.
+pub struct BoardGraph<'b, T, P> {
+    board: &'b Board<T>,
+    passable: P,
+    diagonal: bool,
+}
 
-//     fn len(&self) -> usize {
-//         self.bd.len() * self.bd[0].len()
-//     }
+impl<'b, T, P: Fn(&T) -> bool> BoardGraph<'b, T, P> {
+    /// Grid graph where each cell is adjacent to 4 neighbors.
+    pub fn new(board: &'b Board<T>, passable: P) -> Self {
+        Self {
+            board,
+            passable,
+            diagonal: false,
+        }
+    }
 
-//     fn index(&self, ix: Self::NodeId) -> usize {
-//         ix.0 * self.bd.len() + ix.1
-//     }
+    /// Grid graph where each cell is adjacent to 8 neighbors.
+    pub fn with_diagonal(board: &'b Board<T>, passable: P) -> Self {
+        Self {
+            board,
+            passable,
+            diagonal: true,
+        }
+    }
+}
 
-//     fn neighbors(&'a self, a: Self::NodeId) -> Self::Iter {
-//         let h = self.bd.len();
-//         let w = self.bd[0].len();
+impl<'a, 'b, T, P: Fn(&T) -> bool> Graph<'a> for BoardGraph<'b, T, P> {
+    type NodeId = Ix2;
+    type Iter = std::vec::IntoIter<Ix2>;
 
-//         const VECT: &[(i64, i64)] = &[(0, 1), (0, -1), (1, 0), (-1, 0)];
+    fn len(&self) -> usize {
+        self.board.width() * self.board.height()
+    }
 
-//         // VECT.iter()
-//         //     .map(|&(di, dj)| (a.0 as i64 + di, a.1 as i64 + dj))
-//         //     .filter(|&(i, j)| i >= 0 && i < h && j >= 0 && j < w)
-//         //     .map(|&(i, j)| (i as usize, j as usize))
+    fn index(&self, a: Self::NodeId) -> usize {
+        a.y * self.board.width() + a.x
+    }
 
-//         todo!()
-//     }
-// }
+    fn neighbors(&'a self, a: Self::NodeId) -> Self::Iter {
+        if !(self.passable)(&self.board[a]) {
+            return vec![].into_iter();
+        }
+        let ok = |b: &Ix2| (self.passable)(&self.board[*b]);
+        if self.diagonal {
+            a.neighbor8().filter(ok).collect::<Vec<_>>().into_iter()
+        } else {
+            a.neighbor4().filter(ok).collect::<Vec<_>>().into_iter()
+        }
+    }
+}
 
 //-----
 
@@ -230,104 +437,1721 @@ impl<'a, G: Graph<'a>> Iterator for Bfs<'a, G> {
     }
 }
 
-pub fn bfs<'a, G: Graph<'a, NodeId = usize>>(g: &'a G, start: G::NodeId) -> Bfs<'a, G> {
+pub fn bfs<'a, G: Graph<'a>>(g: &'a G, start: G::NodeId) -> Bfs<'a, G> {
     let n = g.len();
     let mut visited = vec![false; n];
     let mut q = VecDeque::new();
-    visited[start] = true;
+    visited[g.index(start)] = true;
     q.push_back((start, None));
 
     Bfs { visited, q, g }
 }
 
-/// Returns a vector which stores distances from `start`.
-/// For unreachable node, `usize::MAX` is stored.
-pub fn make_dist_table<'a, G: Graph<'a, NodeId = usize>>(
-    g: &'a G,
-    start: G::NodeId,
-) -> Vec<MaybeInf<usize>> {
+/// Returns a vector which stores distances from `start`, indexed by `Graph::index`.
+/// For unreachable node, `Inf` is stored.
+pub fn make_dist_table<'a, G: Graph<'a>>(g: &'a G, start: G::NodeId) -> Vec<MaybeInf<usize>> {
     let mut dist = vec![Inf; g.len()];
-    dist[start] = NonInf(0);
+    dist[g.index(start)] = NonInf(0);
     for (u, v) in bfs(g, start) {
-        dist[v] = dist[u] + 1;
+        dist[g.index(v)] = dist[g.index(u)] + 1;
     }
     dist
 }
 
-// pub fn warshall_floyd<'a>(g: impl Graph<'a, NodeId = usize>) -> Vec<Vec<MaybeInf<usize>>> {
-//     let mut mg = vec![vec![Inf; g.len()]; g.len()];
-//     for u in 0..g.len() {
-//         for v in g.neighbors(u) {
-//             mg[u][v] = NonInf;
-//         }
-//     }
-
-//     for k in 0..g.len() {
-//         for i in 0..g.len() {
-//             for j in 0..g.len() {
-//                 mg[i][j] = min(mg[i][j], mg[i][k] + mg[k][j]);
-//             }
-//         }
-//     }
-//     todo!()
-// }
-
-/*
-fn visit(
-    g: &Vec<Vec<usize>>,
-    v: usize,
-    scc: &mut Vec<Vec<usize>>,
-    s: &mut VecDeque<usize>,
-    ins: &mut Vec<bool>,
-    low: &mut Vec<usize>,
-    num: &mut Vec<usize>,
-    time: usize,
-) {
-    low[v] = time;
-    num[v] = time;
-
-    s.push_back(v);
-    ins[v] = true;
-
-    for &e in g[v].iter() {
-        let w = e;
-        if num[w] == 0 {
-            visit(g, w, scc, s, ins, low, num, time + 1);
-            low[v] = min(low[v], low[w]);
-        } else if ins[w] {
-            low[v] = min(low[v], num[w]);
-        }
-    }
-
-    if low[v] == num[v] {
-        let mut c = vec![];
-        loop {
-            let w = s.pop_back().unwrap();
-            ins[w] = false;
-            c.push(w);
-            if v == w {
-                break;
-            }
-        }
-        scc.push(c);
-    }
-}
-
-pub fn strongly_connected_components(g: &Vec<Vec<usize>>) -> Vec<Vec<usize>> {
+/// 0-1 BFS
+///
+/// Returns distances from `start` and predecessors on shortest paths.
+/// Every weight must be 0 or 1.
+/// O(n + m).
+pub fn bfs01<W: Copy + Into<usize>>(
+    g: &WeightedGraph<W>,
+    start: usize,
+) -> (Vec<MaybeInf<usize>>, Vec<Option<usize>>) {
+    let mut dist = vec![Inf; g.len()];
+    let mut prev = vec![None; g.len()];
+    let mut q = VecDeque::new();
+    dist[start] = NonInf(0);
+    q.push_back((0, start));
+
+    while let Some((d, u)) = q.pop_front() {
+        if dist[u] < NonInf(d) {
+            continue;
+        }
+        for &(v, w) in g[u].iter() {
+            let w = w.into();
+            assert!(w <= 1);
+            if NonInf(d + w) < dist[v] {
+                dist[v] = NonInf(d + w);
+                prev[v] = Some(u);
+                if w == 0 {
+                    q.push_front((d, v));
+                } else {
+                    q.push_back((d + 1, v));
+                }
+            }
+        }
+    }
+
+    (dist, prev)
+}
+
+/// 0-1 BFS with costs given by a function
+///
+/// `cost(u, v)` returns the cost (0 or 1) to move along the edge `u -> v` of `g`.
+/// Returns distances from `start` and predecessors on shortest paths,
+/// both indexed by `Graph::index` like `make_dist_table`.
+/// O(n + m).
+///
+/// For grids, use `BoardGraph` to choose passable cells and 4 or 8 neighbors.
+pub fn bfs01_with<'a, G: Graph<'a>>(
+    g: &'a G,
+    start: G::NodeId,
+    cost: impl Fn(G::NodeId, G::NodeId) -> usize,
+) -> (Vec<MaybeInf<usize>>, Vec<Option<G::NodeId>>) {
+    let mut dist = vec![Inf; g.len()];
+    let mut prev = vec![None; g.len()];
+    let mut q = VecDeque::new();
+    dist[g.index(start)] = NonInf(0);
+    q.push_back((0, start));
+
+    while let Some((d, u)) = q.pop_front() {
+        if dist[g.index(u)] < NonInf(d) {
+            continue;
+        }
+        for v in g.neighbors(u) {
+            let c = cost(u, v);
+            assert!(c <= 1);
+            let iv = g.index(v);
+            if NonInf(d + c) < dist[iv] {
+                dist[iv] = NonInf(d + c);
+                prev[iv] = Some(u);
+                if c == 0 {
+                    q.push_front((d, v));
+                } else {
+                    q.push_back((d + 1, v));
+                }
+            }
+        }
+    }
+
+    (dist, prev)
+}
+
+/// Dijkstra's algorithm
+///
+/// Returns distances from `start` and predecessors on shortest paths.
+/// Weights must be non-negative.
+/// O((n + m) log n).
+pub fn dijkstra<W: Copy + Ord + Zero + Add<Output = W>>(
+    g: &WeightedGraph<W>,
+    start: usize,
+) -> (Vec<MaybeInf<W>>, Vec<Option<usize>>) {
+    dijkstra_multi(g, &[start])
+}
+
+/// Dijkstra's algorithm from multiple sources
+///
+/// Returns distances from the nearest source and predecessors on shortest paths.
+pub fn dijkstra_multi<W: Copy + Ord + Zero + Add<Output = W>>(
+    g: &WeightedGraph<W>,
+    starts: &[usize],
+) -> (Vec<MaybeInf<W>>, Vec<Option<usize>>) {
+    let mut dist = vec![Inf; g.len()];
+    let mut prev = vec![None; g.len()];
+    let mut q = BinaryHeap::new();
+
+    for &s in starts.iter() {
+        dist[s] = NonInf(W::zero());
+        q.push(Reverse((W::zero(), s)));
+    }
+
+    while let Some(Reverse((d, u))) = q.pop() {
+        if dist[u] < NonInf(d) {
+            continue;
+        }
+        for &(v, w) in g[u].iter() {
+            let nd = d + w;
+            if NonInf(nd) < dist[v] {
+                dist[v] = NonInf(nd);
+                prev[v] = Some(u);
+                q.push(Reverse((nd, v)));
+            }
+        }
+    }
+
+    (dist, prev)
+}
+
+/// Restores the path to `t` from the predecessor table.
+///
+/// The result begins with a source and ends with `t`.
+pub fn restore_path(prev: &[Option<usize>], t: usize) -> Vec<usize> {
+    let mut path = vec![t];
+    let mut cur = t;
+    while let Some(p) = prev[cur] {
+        path.push(p);
+        cur = p;
+    }
+    path.reverse();
+    path
+}
+
+/// Warshall-Floyd algorithm
+///
+/// Returns distances between all pairs of nodes.
+/// `NegInf` is stored for pairs which have a path through a negative cycle.
+/// O(n^3).
+pub fn warshall_floyd<W: Copy + Ord + Zero + Add<Output = W>>(
+    g: &WeightedGraph<W>,
+) -> Vec<Vec<MaybeInf<W>>> {
+    let n = g.len();
+    let mut d = vec![vec![Inf; n]; n];
+    for (u, es) in g.iter().enumerate() {
+        d[u][u] = NonInf(W::zero());
+        for &(v, w) in es.iter() {
+            d[u][v] = min(d[u][v], NonInf(w));
+        }
+    }
+
+    for k in 0..n {
+        for i in 0..n {
+            if d[i][k] == Inf {
+                continue;
+            }
+            for j in 0..n {
+                if d[k][j] != Inf {
+                    d[i][j] = min(d[i][j], d[i][k] + d[k][j]);
+                }
+            }
+        }
+        // Clamp as soon as a negative cycle is found so that values do not blow up
+        for (i, row) in d.iter_mut().enumerate() {
+            if row[i] < NonInf(W::zero()) {
+                row[i] = NegInf;
+            }
+        }
+    }
+
+    for k in 0..n {
+        if d[k][k] != NegInf {
+            continue;
+        }
+        let dk = d[k].clone();
+        for row in d.iter_mut() {
+            if row[k] == Inf {
+                continue;
+            }
+            for (x, &y) in row.iter_mut().zip(dk.iter()) {
+                if y != Inf {
+                    *x = NegInf;
+                }
+            }
+        }
+    }
+
+    d
+}
+
+/// Bellman-Ford algorithm
+///
+/// Returns distances from `start`.
+/// `NegInf` is stored for nodes which are reachable from a negative cycle.
+/// O(nm).
+pub fn bellman_ford<W: Copy + Ord + Add<Output = W> + Zero>(
+    g: &WeightedGraph<W>,
+    start: usize,
+) -> Vec<MaybeInf<W>> {
     let n = g.len();
+    let mut dist = vec![Inf; n];
+    dist[start] = NonInf(W::zero());
+
+    // After `n - 1` rounds, nodes still being updated are affected by negative cycles
+    for round in 0..n * 2 {
+        let mut updated = false;
+        for u in 0..n {
+            if dist[u] == Inf {
+                continue;
+            }
+            for &(v, w) in g[u].iter() {
+                let nd = dist[u] + w;
+                if nd < dist[v] {
+                    dist[v] = if round + 1 >= n { NegInf } else { nd };
+                    updated = true;
+                }
+            }
+        }
+        if !updated {
+            break;
+        }
+    }
+
+    dist
+}
 
-    let mut num = vec![0; n];
+/// Strongly connected components by Tarjan's algorithm
+///
+/// Components are returned in topological order,
+/// i.e. every edge goes to the same or a later component.
+/// O(n + m).
+pub fn strongly_connected_components<'a, G: Graph<'a, NodeId = usize>>(
+    g: &'a G,
+) -> Vec<Vec<usize>> {
+    let n = g.len();
+    let mut ord = vec![usize::MAX; n];
     let mut low = vec![0; n];
-    let mut s = VecDeque::new();
     let mut ins = vec![false; n];
+    let mut s = vec![];
     let mut scc = vec![];
+    let mut time = 0;
+
+    for root in 0..n {
+        if ord[root] != usize::MAX {
+            continue;
+        }
+
+        ord[root] = time;
+        low[root] = time;
+        time += 1;
+        s.push(root);
+        ins[root] = true;
+        let mut stack = vec![(root, g.neighbors(root))];
+
+        while let Some((u, it)) = stack.last_mut() {
+            let u = *u;
+            if let Some(v) = it.next() {
+                if ord[v] == usize::MAX {
+                    ord[v] = time;
+                    low[v] = time;
+                    time += 1;
+                    s.push(v);
+                    ins[v] = true;
+                    stack.push((v, g.neighbors(v)));
+                } else if ins[v] {
+                    low[u] = min(low[u], ord[v]);
+                }
+                continue;
+            }
 
-    for u in 0..n {
-        if num[u] == 0 {
-            visit(g, u, &mut scc, &mut s, &mut ins, &mut low, &mut num, 1);
+            stack.pop();
+            if let Some(&(p, _)) = stack.last() {
+                low[p] = min(low[p], low[u]);
+            }
+            if low[u] == ord[u] {
+                let mut c = vec![];
+                loop {
+                    let w = s.pop().unwrap();
+                    ins[w] = false;
+                    c.push(w);
+                    if w == u {
+                        break;
+                    }
+                }
+                scc.push(c);
+            }
         }
     }
 
+    // Tarjan's algorithm finds components in reverse topological order
+    scc.reverse();
     scc
 }
-*/
+
+/// Condensation of the graph
+///
+/// Returns the component id of each node and the DAG of components.
+/// Component ids are in topological order and the DAG has no multi-edges.
+pub fn condense<'a, G: Graph<'a, NodeId = usize>>(g: &'a G) -> (Vec<usize>, UnweightedGraph) {
+    let scc = strongly_connected_components(g);
+    let mut id = vec![0; g.len()];
+    for (i, c) in scc.iter().enumerate() {
+        for &u in c.iter() {
+            id[u] = i;
+        }
+    }
+
+    let mut dag = vec![vec![]; scc.len()];
+    for u in 0..g.len() {
+        for v in g.neighbors(u) {
+            if id[u] != id[v] {
+                dag[id[u]].push(id[v]);
+            }
+        }
+    }
+    for es in dag.iter_mut() {
+        es.sort_unstable();
+        es.dedup();
+    }
+
+    (id, dag)
+}
+
+// Recover the edge list of an undirected graph made by `make_undirected_graph`.
+// Each undirected edge appears twice in the adjacency list (a self-loop twice in the same list).
+fn undirected_edges(g: &[Vec<usize>]) -> Vec<(usize, usize)> {
+    let mut edges = vec![];
+    for (u, es) in g.iter().enumerate() {
+        let mut loops = 0;
+        for &v in es.iter() {
+            if u < v {
+                edges.push((u, v));
+            } else if u == v {
+                loops += 1;
+                if loops & 1 == 0 {
+                    edges.push((u, u));
+                }
+            }
+        }
+    }
+    edges
+}
+
+// DFS tree with ord/low values of an undirected graph
+struct LowLink {
+    edges: Vec<(usize, usize)>,
+    ord: Vec<usize>,
+    low: Vec<usize>,
+    // parent in the DFS tree, `usize::MAX` for roots
+    par: Vec<usize>,
+    // id of the tree edge to the parent, `usize::MAX` for roots
+    par_edge: Vec<usize>,
+    order: Vec<usize>,
+}
+
+impl LowLink {
+    fn new(g: &[Vec<usize>]) -> Self {
+        let n = g.len();
+        let edges = undirected_edges(g);
+        let mut adj = vec![vec![]; n];
+        for (i, &(u, v)) in edges.iter().enumerate() {
+            adj[u].push((v, i));
+            if u != v {
+                adj[v].push((u, i));
+            }
+        }
+
+        let mut ord = vec![usize::MAX; n];
+        let mut low = vec![0; n];
+        let mut par = vec![usize::MAX; n];
+        let mut par_edge = vec![usize::MAX; n];
+        let mut order = Vec::with_capacity(n);
+
+        for root in 0..n {
+            if ord[root] != usize::MAX {
+                continue;
+            }
+            ord[root] = order.len();
+            low[root] = order.len();
+            order.push(root);
+            let mut stack = vec![(root, adj[root].iter())];
+
+            while let Some((u, it)) = stack.last_mut() {
+                let u = *u;
+                if let Some(&(v, id)) = it.next() {
+                    // skip the tree edge itself, not every edge to the parent
+                    if id == par_edge[u] {
+                        continue;
+                    }
+                    if ord[v] == usize::MAX {
+                        ord[v] = order.len();
+                        low[v] = order.len();
+                        order.push(v);
+                        par[v] = u;
+                        par_edge[v] = id;
+                        stack.push((v, adj[v].iter()));
+                    } else {
+                        low[u] = min(low[u], ord[v]);
+                    }
+                    continue;
+                }
+
+                stack.pop();
+                if let Some(&(p, _)) = stack.last() {
+                    low[p] = min(low[p], low[u]);
+                }
+            }
+        }
+
+        Self {
+            edges,
+            ord,
+            low,
+            par,
+            par_edge,
+            order,
+        }
+    }
+
+    fn is_bridge(&self, v: usize) -> bool {
+        self.par[v] != usize::MAX && self.low[v] > self.ord[self.par[v]]
+    }
+
+    // the tree edge to `v` starts a new biconnected component
+    fn is_block_head(&self, v: usize) -> bool {
+        self.par[v] != usize::MAX && self.low[v] >= self.ord[self.par[v]]
+    }
+}
+
+/// Bridges and articulation points of an undirected graph
+///
+/// Returns bridges as `(u, v)` with `u <= v`, and articulation points, both sorted.
+/// Multi-edges are never bridges.
+/// O(n + m).
+pub fn lowlink(g: &UnweightedGraph) -> (Vec<(usize, usize)>, Vec<usize>) {
+    let ll = LowLink::new(g);
+    let n = g.len();
+
+    let mut bridges = (0..n)
+        .filter(|&v| ll.is_bridge(v))
+        .map(|v| ll.edges[ll.par_edge[v]])
+        .collect::<Vec<_>>();
+    bridges.sort_unstable();
+
+    // number of children which start a new biconnected component
+    let mut heads = vec![0; n];
+    for v in 0..n {
+        if ll.is_block_head(v) {
+            heads[ll.par[v]] += 1;
+        }
+    }
+    // a root is an articulation point iff it has two or more children
+    let articulations = (0..n)
+        .filter(|&v| heads[v] >= if ll.par[v] == usize::MAX { 2 } else { 1 })
+        .collect();
+
+    (bridges, articulations)
+}
+
+/// 2-edge-connected components of an undirected graph
+///
+/// Returns the components, i.e. the connected components after removing all bridges.
+/// O(n + m).
+pub fn two_edge_connected_components(g: &UnweightedGraph) -> Vec<Vec<usize>> {
+    let ll = LowLink::new(g);
+    let mut id = vec![0; g.len()];
+    let mut ret: Vec<Vec<usize>> = vec![];
+    for &v in ll.order.iter() {
+        if ll.par[v] == usize::MAX || ll.is_bridge(v) {
+            id[v] = ret.len();
+            ret.push(vec![]);
+        } else {
+            id[v] = id[ll.par[v]];
+        }
+        ret[id[v]].push(v);
+    }
+    ret
+}
+
+/// Biconnected (2-vertex-connected) components of an undirected graph
+///
+/// Returns the node sets of the components.
+/// An articulation point belongs to several components, and an isolated node forms its own component.
+/// O(n + m).
+pub fn biconnected_components(g: &UnweightedGraph) -> Vec<Vec<usize>> {
+    let ll = LowLink::new(g);
+    // id of the component which contains the tree edge to the node
+    let mut id = vec![usize::MAX; g.len()];
+    let mut ret: Vec<Vec<usize>> = vec![];
+    for &v in ll.order.iter() {
+        let p = ll.par[v];
+        if p == usize::MAX {
+            if g[v].iter().all(|&w| w == v) {
+                ret.push(vec![v]);
+            }
+        } else if ll.is_block_head(v) {
+            id[v] = ret.len();
+            ret.push(vec![p, v]);
+        } else {
+            id[v] = id[p];
+            ret[id[v]].push(v);
+        }
+    }
+    ret
+}
+
+/// Block-cut tree of an undirected graph
+///
+/// Nodes `0..n` are the original nodes and node `n + i` is the `i`-th component of
+/// `biconnected_components(g)`. Each original node is connected to the components containing it.
+/// Returns the forest and the number of components.
+pub fn block_cut_tree(g: &UnweightedGraph) -> (UnweightedGraph, usize) {
+    let n = g.len();
+    let bcc = biconnected_components(g);
+    let mut edges = vec![];
+    for (i, c) in bcc.iter().enumerate() {
+        for &v in c.iter() {
+            edges.push((v, n + i));
+        }
+    }
+    (make_undirected_graph(n + bcc.len(), &edges), bcc.len())
+}
+
+// Hierholzer's algorithm
+// Returns the nodes and the edge ids of an Eulerian trail.
+fn hierholzer(
+    n: usize,
+    edges: &[(usize, usize)],
+    directed: bool,
+) -> Option<(Vec<usize>, Vec<usize>)> {
+    let mut adj = vec![vec![]; n];
+    // out-degree minus in-degree, or degree for undirected graphs
+    let mut deg = vec![0_i64; n];
+    for (i, &(u, v)) in edges.iter().enumerate() {
+        adj[u].push((v, i));
+        if directed {
+            deg[u] += 1;
+            deg[v] -= 1;
+        } else {
+            adj[v].push((u, i));
+            deg[u] += 1;
+            deg[v] += 1;
+        }
+    }
+
+    let start = if directed {
+        if deg.iter().any(|&d| d.abs() > 1) || deg.iter().filter(|&&d| d == 1).count() > 1 {
+            return None;
+        }
+        deg.iter().position(|&d| d == 1)
+    } else {
+        let odd = deg.iter().filter(|&&d| d & 1 != 0).count();
+        if odd > 2 {
+            return None;
+        }
+        deg.iter().position(|&d| d & 1 != 0)
+    };
+    let start = match start.or_else(|| adj.iter().position(|es| !es.is_empty())) {
+        Some(s) => s,
+        None if n == 0 => return Some((vec![], vec![])),
+        None => return Some((vec![0], vec![])),
+    };
+
+    let mut used = vec![false; edges.len()];
+    let mut it = vec![0; n];
+    let mut trail = vec![];
+    let mut stack = vec![(start, usize::MAX)];
+    while let Some(&(u, e)) = stack.last() {
+        while it[u] < adj[u].len() && used[adj[u][it[u]].1] {
+            it[u] += 1;
+        }
+        if let Some(&(v, id)) = adj[u].get(it[u]) {
+            used[id] = true;
+            stack.push((v, id));
+        } else {
+            stack.pop();
+            trail.push((u, e));
+        }
+    }
+
+    // some edges are not reachable from `start`
+    if trail.len() != edges.len() + 1 {
+        return None;
+    }
+    trail.reverse();
+    let nodes = trail.iter().map(|&(u, _)| u).collect();
+    let ids = trail[1..].iter().map(|&(_, e)| e).collect();
+    Some((nodes, ids))
+}
+
+/// Eulerian trail
+///
+/// Returns the sequence of nodes of a trail which uses every edge exactly once, if exists.
+/// A circuit is returned when exists. `g` is made by `make_directed_graph` or `make_undirected_graph`.
+/// For a graph without edges, returns `[0]`.
+/// O(n + m).
+pub fn eulerian_path(g: &UnweightedGraph, directed: bool) -> Option<Vec<usize>> {
+    let edges = if directed {
+        g.iter()
+            .enumerate()
+            .flat_map(|(u, es)| es.iter().map(move |&v| (u, v)))
+            .collect()
+    } else {
+        undirected_edges(g)
+    };
+    hierholzer(g.len(), &edges, directed).map(|(nodes, _)| nodes)
+}
+
+/// Eulerian trail of a multigraph
+///
+/// Same as `eulerian_path`, but takes the arguments of `make_directed_graph` or
+/// `make_undirected_graph` and returns the indices of `edges` in the order of the trail.
+/// An undirected edge may be traversed in either direction.
+/// O(n + m).
+pub fn eulerian_trail(n: usize, edges: &[(usize, usize)], directed: bool) -> Option<Vec<usize>> {
+    hierholzer(n, edges, directed).map(|(_, ids)| ids)
+}
+
+/// Kruskal's algorithm
+///
+/// Returns the total weight and indices of edges in the minimum spanning forest.
+/// O(m log m).
+pub fn kruskal<W: Copy + Ord + Zero + Add<Output = W>>(
+    n: usize,
+    edges: &[(usize, usize, W)],
+) -> (W, Vec<usize>) {
+    let mut ixs = (0..edges.len()).collect::<Vec<_>>();
+    ixs.sort_by_key(|&i| edges[i].2);
+
+    let mut uf = UnionFind::new(n);
+    let mut total = W::zero();
+    let mut ret = vec![];
+    for i in ixs {
+        let (u, v, w) = edges[i];
+        if uf.union(u, v) {
+            total = total + w;
+            ret.push(i);
+        }
+    }
+    (total, ret)
+}
+
+/// Prim's algorithm for dense graphs
+///
+/// Returns the total weight and edges `(parent, child, weight)` in the minimum spanning forest.
+/// `g` must be undirected.
+/// O(n^2 + m).
+pub fn prim<W: Copy + Ord + Zero + Add<Output = W>>(
+    g: &WeightedGraph<W>,
+) -> (W, Vec<(usize, usize, W)>) {
+    let n = g.len();
+    let mut used = vec![false; n];
+    let mut best: Vec<Option<(W, usize)>> = vec![None; n];
+    let mut total = W::zero();
+    let mut ret = vec![];
+
+    for _ in 0..n {
+        // unreached nodes become roots of new trees
+        let u = (0..n)
+            .filter(|&v| !used[v])
+            .min_by_key(|&v| best[v].map_or(Inf, |(w, _)| NonInf(w)))
+            .unwrap();
+        used[u] = true;
+        if let Some((w, p)) = best[u] {
+            total = total + w;
+            ret.push((p, u, w));
+        }
+        for &(v, w) in g[u].iter() {
+            if !used[v] && best[v].is_none_or(|(bw, _)| w < bw) {
+                best[v] = Some((w, u));
+            }
+        }
+    }
+
+    (total, ret)
+}
+
+fn in_degrees<'a, G: Graph<'a, NodeId = usize>>(g: &'a G) -> Vec<usize> {
+    let mut deg = vec![0; g.len()];
+    for u in 0..g.len() {
+        for v in g.neighbors(u) {
+            deg[v] += 1;
+        }
+    }
+    deg
+}
+
+// Finds a cycle by DFS. The result lists nodes along the cycle.
+fn find_cycle<'a, G: Graph<'a, NodeId = usize>>(g: &'a G) -> Option<Vec<usize>> {
+    // 0: unvisited, 1: on the stack, 2: done
+    let mut state = vec![0; g.len()];
+    for root in 0..g.len() {
+        if state[root] != 0 {
+            continue;
+        }
+        state[root] = 1;
+        let mut stack = vec![(root, g.neighbors(root))];
+        while let Some((u, it)) = stack.last_mut() {
+            let u = *u;
+            if let Some(v) = it.next() {
+                if state[v] == 0 {
+                    state[v] = 1;
+                    stack.push((v, g.neighbors(v)));
+                } else if state[v] == 1 {
+                    let pos = stack.iter().position(|r| r.0 == v).unwrap();
+                    return Some(stack[pos..].iter().map(|r| r.0).collect());
+                }
+            } else {
+                state[u] = 2;
+                stack.pop();
+            }
+        }
+    }
+    None
+}
+
+/// Topological sort by Kahn's algorithm
+///
+/// Returns `Err(cycle)` with nodes along a cycle if the graph is not a DAG.
+/// O(n + m).
+pub fn topological_sort<'a, G: Graph<'a, NodeId = usize>>(
+    g: &'a G,
+) -> Result<Vec<usize>, Vec<usize>> {
+    let mut deg = in_degrees(g);
+    let mut q = (0..g.len())
+        .filter(|&u| deg[u] == 0)
+        .collect::<VecDeque<_>>();
+    let mut ret = Vec::with_capacity(g.len());
+
+    while let Some(u) = q.pop_front() {
+        ret.push(u);
+        for v in g.neighbors(u) {
+            deg[v] -= 1;
+            if deg[v] == 0 {
+                q.push_back(v);
+            }
+        }
+    }
+
+    if ret.len() == g.len() {
+        Ok(ret)
+    } else {
+        Err(find_cycle(g).unwrap())
+    }
+}
+
+/// Lexicographically smallest topological order
+///
+/// Returns `Err(cycle)` with nodes along a cycle if the graph is not a DAG.
+/// O(n log n + m).
+pub fn topological_sort_lex<'a, G: Graph<'a, NodeId = usize>>(
+    g: &'a G,
+) -> Result<Vec<usize>, Vec<usize>> {
+    let mut deg = in_degrees(g);
+    let mut q = (0..g.len())
+        .filter(|&u| deg[u] == 0)
+        .map(Reverse)
+        .collect::<BinaryHeap<_>>();
+    let mut ret = Vec::with_capacity(g.len());
+
+    while let Some(Reverse(u)) = q.pop() {
+        ret.push(u);
+        for v in g.neighbors(u) {
+            deg[v] -= 1;
+            if deg[v] == 0 {
+                q.push(Reverse(v));
+            }
+        }
+    }
+
+    if ret.len() == g.len() {
+        Ok(ret)
+    } else {
+        Err(find_cycle(g).unwrap())
+    }
+}
+
+/// Longest path in DAG
+///
+/// Returns the number of edges of the longest path which ends at each node.
+/// Returns `Err(cycle)` with nodes along a cycle if the graph is not a DAG.
+/// O(n + m).
+pub fn longest_path<'a, G: Graph<'a, NodeId = usize>>(g: &'a G) -> Result<Vec<usize>, Vec<usize>> {
+    let ord = topological_sort(g)?;
+    let mut dp = vec![0; g.len()];
+    for &u in ord.iter() {
+        for v in g.neighbors(u) {
+            dp[v] = max(dp[v], dp[u] + 1);
+        }
+    }
+    Ok(dp)
+}
+
+/// Bipartite check
+///
+/// Returns a 2-coloring of an undirected graph if exists.
+/// O(n + m).
+pub fn is_bipartite<'a, G: Graph<'a, NodeId = usize>>(g: &'a G) -> Option<Vec<bool>> {
+    let n = g.len();
+    let mut color: Vec<Option<bool>> = vec![None; n];
+    let mut q = VecDeque::new();
+    for s in 0..n {
+        if color[s].is_some() {
+            continue;
+        }
+        color[s] = Some(false);
+        q.push_back(s);
+        while let Some(u) = q.pop_front() {
+            let c = color[u].unwrap();
+            for v in g.neighbors(u) {
+                match color[v] {
+                    None => {
+                        color[v] = Some(!c);
+                        q.push_back(v);
+                    }
+                    Some(d) if d == c => return None,
+                    _ => {}
+                }
+            }
+        }
+    }
+    Some(color.into_iter().map(Option::unwrap).collect())
+}
+
+/// Maximum bipartite matching by Hopcroft-Karp algorithm
+///
+/// `edges` are pairs of a left node in `0..left` and a right node in `0..right`.
+/// Returns matched pairs `(left node, right node)`.
+/// O(m sqrt(n)).
+pub fn bipartite_matching(
+    left: usize,
+    right: usize,
+    edges: &[(usize, usize)],
+) -> Vec<(usize, usize)> {
+    let g = make_directed_graph(left, edges);
+    let mut match_l = vec![usize::MAX; left];
+    let mut match_r = vec![usize::MAX; right];
+
+    loop {
+        // BFS layers of left nodes from free left nodes
+        let mut dist = vec![usize::MAX; left];
+        let mut q = VecDeque::new();
+        for u in 0..left {
+            if match_l[u] == usize::MAX {
+                dist[u] = 0;
+                q.push_back(u);
+            }
+        }
+        let mut found = false;
+        while let Some(u) = q.pop_front() {
+            for &v in g[u].iter() {
+                let w = match_r[v];
+                if w == usize::MAX {
+                    found = true;
+                } else if dist[w] == usize::MAX {
+                    dist[w] = dist[u] + 1;
+                    q.push_back(w);
+                }
+            }
+        }
+        if !found {
+            break;
+        }
+
+        // DFS along layers to find vertex-disjoint augmenting paths
+        let mut it = vec![0; left];
+        for root in 0..left {
+            if match_l[root] != usize::MAX {
+                continue;
+            }
+            let mut stack = vec![root];
+            while let Some(&u) = stack.last() {
+                if it[u] == g[u].len() {
+                    // dead end
+                    dist[u] = usize::MAX;
+                    stack.pop();
+                    if let Some(&p) = stack.last() {
+                        it[p] += 1;
+                    }
+                    continue;
+                }
+                let w = match_r[g[u][it[u]]];
+                if w == usize::MAX {
+                    for &x in stack.iter() {
+                        let v = g[x][it[x]];
+                        match_l[x] = v;
+                        match_r[v] = x;
+                    }
+                    break;
+                } else if dist[w] == dist[u] + 1 {
+                    stack.push(w);
+                } else {
+                    it[u] += 1;
+                }
+            }
+        }
+    }
+
+    (0..left)
+        .filter(|&u| match_l[u] != usize::MAX)
+        .map(|u| (u, match_l[u]))
+        .collect()
+}
+
+/// 2-SAT solver
+///
+/// A literal `(i, f)` means `x_i = f`.
+pub struct TwoSat {
+    n: usize,
+    // node `2 * i + f` stands for the literal `x_i = f`
+    g: UnweightedGraph,
+}
+
+impl TwoSat {
+    /// Create a problem with `n` variables.
+    pub fn new(n: usize) -> Self {
+        Self {
+            n,
+            g: vec![vec![]; n * 2],
+        }
+    }
+
+    fn node(i: usize, f: bool) -> usize {
+        i * 2 + f as usize
+    }
+
+    fn new_var(&mut self) -> usize {
+        self.g.push(vec![]);
+        self.g.push(vec![]);
+        self.g.len() / 2 - 1
+    }
+
+    /// Add the clause `(x_i = f) || (x_j = g)`.
+    pub fn add_clause(&mut self, i: usize, f: bool, j: usize, g: bool) {
+        self.g[Self::node(i, !f)].push(Self::node(j, g));
+        self.g[Self::node(j, !g)].push(Self::node(i, f));
+    }
+
+    /// Add the constraint `(x_i = f) => (x_j = g)`.
+    pub fn implies(&mut self, i: usize, f: bool, j: usize, g: bool) {
+        self.add_clause(i, !f, j, g);
+    }
+
+    /// Add the constraint that exactly one of `x_i = f` and `x_j = g` holds.
+    ///
+    /// Exactly-one over more than two literals can not be expressed in 2-SAT.
+    pub fn exactly_one(&mut self, i: usize, f: bool, j: usize, g: bool) {
+        self.add_clause(i, f, j, g);
+        self.add_clause(i, !f, j, !g);
+    }
+
+    /// Add the constraint that at most one of `lits` holds.
+    ///
+    /// This uses O(|lits|) auxiliary variables and clauses.
+    pub fn at_most_one(&mut self, lits: &[(usize, bool)]) {
+        // `p_k` means one of `lits[..=k]` holds
+        let mut prev: Option<usize> = None;
+        for &(i, f) in lits.iter() {
+            let p = self.new_var();
+            self.implies(i, f, p, true);
+            if let Some(q) = prev {
+                self.implies(q, true, p, true);
+                self.implies(q, true, i, !f);
+            }
+            prev = Some(p);
+        }
+    }
+
+    /// Returns an assignment which satisfies all constraints if exists.
+    pub fn satisfiable(&self) -> Option<Vec<bool>> {
+        let (id, _) = condense(&self.g);
+        let mut ret = Vec::with_capacity(self.n);
+        for i in 0..self.n {
+            let t = id[Self::node(i, true)];
+            let f = id[Self::node(i, false)];
+            if t == f {
+                return None;
+            }
+            ret.push(t > f);
+        }
+        // auxiliary variables can be inconsistent too
+        for i in self.n..self.g.len() / 2 {
+            if id[Self::node(i, true)] == id[Self::node(i, false)] {
+                return None;
+            }
+        }
+        Some(ret)
+    }
+}
+
+#[test]
+fn test_dijkstra() {
+    let g = make_weighted_directed_graph(
+        6,
+        &[
+            (0, 1, 7),
+            (0, 2, 2),
+            (2, 1, 3),
+            (1, 3, 1),
+            (2, 3, 8),
+            (3, 4, 0),
+            (5, 0, 1),
+        ],
+    );
+
+    let (dist, prev) = dijkstra(&g, 0);
+    assert_eq!(
+        dist,
+        vec![NonInf(0), NonInf(5), NonInf(2), NonInf(6), NonInf(6), Inf]
+    );
+    assert_eq!(restore_path(&prev, 4), vec![0, 2, 1, 3, 4]);
+    assert_eq!(restore_path(&prev, 0), vec![0]);
+    assert_eq!(prev[5], None);
+
+    let (dist, prev) = dijkstra_multi(&g, &[1, 5]);
+    assert_eq!(
+        dist,
+        vec![
+            NonInf(1),
+            NonInf(0),
+            NonInf(3),
+            NonInf(1),
+            NonInf(1),
+            NonInf(0)
+        ]
+    );
+    assert_eq!(restore_path(&prev, 2), vec![5, 0, 2]);
+    assert_eq!(restore_path(&prev, 4), vec![1, 3, 4]);
+}
+
+#[test]
+fn test_shortest_paths_with_negative_edges() {
+    let g =
+        make_weighted_directed_graph(4, &[(0, 1, 4), (0, 2, 5), (1, 2, -3), (2, 3, 2), (3, 1, 1)]);
+    assert_eq!(
+        bellman_ford(&g, 0),
+        vec![NonInf(0), NonInf(4), NonInf(1), NonInf(3)]
+    );
+    let d = warshall_floyd(&g);
+    assert_eq!(d[0], vec![NonInf(0), NonInf(4), NonInf(1), NonInf(3)]);
+    assert_eq!(d[1], vec![Inf, NonInf(0), NonInf(-3), NonInf(-1)]);
+    assert_eq!(d[3][2], NonInf(-2));
+
+    // 1 -> 2 -> 3 -> 1 is a negative cycle, which is not reachable to 4
+    let g = make_weighted_directed_graph(
+        6,
+        &[
+            (0, 1, 1),
+            (1, 2, 1),
+            (2, 3, -5),
+            (3, 1, 1),
+            (3, 5, 1),
+            (4, 0, 1),
+        ],
+    );
+    assert_eq!(
+        bellman_ford(&g, 0),
+        vec![NonInf(0), NegInf, NegInf, NegInf, Inf, NegInf]
+    );
+    assert_eq!(
+        bellman_ford(&g, 5),
+        vec![Inf, Inf, Inf, Inf, Inf, NonInf(0)]
+    );
+    let d = warshall_floyd(&g);
+    assert_eq!(
+        d[4],
+        vec![NonInf(1), NegInf, NegInf, NegInf, NonInf(0), NegInf]
+    );
+    assert_eq!(d[0][4], Inf);
+    assert_eq!(d[5][5], NonInf(0));
+    assert_eq!(d[1][1], NegInf);
+}
+
+#[test]
+fn test_scc() {
+    let g = make_directed_graph(
+        8,
+        &[
+            (0, 1),
+            (1, 2),
+            (2, 0),
+            (2, 3),
+            (3, 4),
+            (4, 5),
+            (5, 3),
+            (6, 5),
+            (6, 7),
+            (7, 6),
+            (1, 4),
+        ],
+    );
+
+    let mut scc = strongly_connected_components(&g);
+    for c in scc.iter_mut() {
+        c.sort_unstable();
+    }
+    assert_eq!(scc.len(), 3);
+    assert!(scc.contains(&vec![0, 1, 2]));
+    assert!(scc.contains(&vec![3, 4, 5]));
+    assert!(scc.contains(&vec![6, 7]));
+
+    let (id, dag) = condense(&g);
+    for u in 0..g.len() {
+        for &v in g[u].iter() {
+            assert!(id[u] <= id[v]);
+        }
+    }
+    assert_eq!(id[0], id[2]);
+    assert_eq!(dag[id[0]], vec![id[3]]);
+    assert_eq!(dag[id[6]], vec![id[3]]);
+    assert!(dag[id[3]].is_empty());
+
+    // long path must not overflow the stack
+    let n = 1_000_000;
+    let edges = (1..n).map(|i| (i - 1, i)).collect::<Vec<_>>();
+    let scc = strongly_connected_components(&make_directed_graph(n, &edges));
+    assert_eq!(scc.len(), n);
+    assert!(scc.iter().enumerate().all(|(i, c)| c == &vec![i]));
+}
+
+#[test]
+fn test_two_sat() {
+    let check = |n: usize, clauses: &[(usize, bool, usize, bool)], x: &[bool]| {
+        x.len() == n && clauses.iter().all(|&(i, f, j, g)| x[i] == f || x[j] == g)
+    };
+
+    let clauses = [
+        (0, true, 1, false),
+        (1, true, 2, true),
+        (0, false, 2, false),
+        (2, true, 3, true),
+    ];
+    let mut ts = TwoSat::new(4);
+    for &(i, f, j, g) in clauses.iter() {
+        ts.add_clause(i, f, j, g);
+    }
+    let x = ts.satisfiable().unwrap();
+    assert!(check(4, &clauses, &x));
+
+    // x_0 and !x_0
+    let mut ts = TwoSat::new(2);
+    ts.add_clause(0, true, 0, true);
+    ts.add_clause(0, false, 1, false);
+    ts.implies(1, false, 0, false);
+    assert_eq!(ts.satisfiable(), None);
+
+    let mut ts = TwoSat::new(2);
+    ts.exactly_one(0, true, 1, true);
+    ts.add_clause(1, false, 1, false);
+    assert_eq!(ts.satisfiable(), Some(vec![true, false]));
+
+    let mut ts = TwoSat::new(5);
+    ts.at_most_one(&[(0, true), (1, true), (2, false), (3, true)]);
+    ts.add_clause(2, true, 2, true);
+    ts.add_clause(3, true, 4, true);
+    ts.add_clause(4, false, 4, false);
+    assert_eq!(
+        ts.satisfiable(),
+        Some(vec![false, false, true, true, false])
+    );
+    ts.add_clause(0, true, 1, true);
+    assert_eq!(ts.satisfiable(), None);
+}
+
+#[test]
+fn test_topological_sort() {
+    let g = make_directed_graph(6, &[(5, 2), (5, 0), (4, 0), (4, 1), (2, 3), (3, 1)]);
+
+    let ord = topological_sort(&g).unwrap();
+    let mut pos = vec![0; g.len()];
+    for (i, &u) in ord.iter().enumerate() {
+        pos[u] = i;
+    }
+    for u in 0..g.len() {
+        for &v in g[u].iter() {
+            assert!(pos[u] < pos[v]);
+        }
+    }
+
+    assert_eq!(topological_sort_lex(&g), Ok(vec![4, 5, 0, 2, 3, 1]));
+    assert_eq!(longest_path(&g), Ok(vec![1, 3, 1, 2, 0, 0]));
+
+    let g = make_directed_graph(5, &[(0, 1), (1, 2), (2, 3), (3, 1), (3, 4)]);
+    let cycle = topological_sort(&g).unwrap_err();
+    assert_eq!(cycle, vec![1, 2, 3]);
+    assert!(topological_sort_lex(&g).is_err());
+    assert!(longest_path(&g).is_err());
+
+    let g = make_directed_graph(2, &[(1, 1)]);
+    assert_eq!(topological_sort(&g), Err(vec![1]));
+}
+
+#[test]
+fn test_minimum_spanning_tree() {
+    let edges = [
+        (0, 1, 4),
+        (0, 2, 3),
+        (1, 2, 1),
+        (1, 3, 2),
+        (2, 3, 4),
+        (3, 4, 2),
+        (4, 5, 6),
+        (6, 7, 5),
+        (6, 7, 1),
+    ];
+
+    let (total, mut es) = kruskal(8, &edges);
+    es.sort_unstable();
+    assert_eq!(total, 15);
+    assert_eq!(es, vec![1, 2, 3, 5, 6, 8]);
+
+    let g = make_weighted_undirected_graph(8, &edges);
+    let (total, es) = prim(&g);
+    assert_eq!(total, 15);
+    assert_eq!(es.len(), 6);
+    assert_eq!(es.iter().map(|e| e.2).sum::<i32>(), 15);
+    assert!(es.contains(&(6, 7, 1)));
+}
+
+#[test]
+fn test_bipartite() {
+    use crate::test_util::rng;
+
+    let g = make_undirected_graph(6, &[(0, 1), (1, 2), (2, 3), (3, 0), (4, 5)]);
+    let color = is_bipartite(&g).unwrap();
+    for u in 0..g.len() {
+        for &v in g[u].iter() {
+            assert_ne!(color[u], color[v]);
+        }
+    }
+
+    let g = make_undirected_graph(5, &[(0, 1), (1, 2), (2, 3), (3, 4), (4, 0)]);
+    assert_eq!(is_bipartite(&g), None);
+
+    let edges = [
+        (0, 0),
+        (0, 1),
+        (1, 0),
+        (2, 1),
+        (2, 2),
+        (3, 2),
+        (3, 3),
+        (4, 3),
+    ];
+    let m = bipartite_matching(5, 4, &edges);
+    assert_eq!(m.len(), 4);
+    let mut used_r = [false; 4];
+    for &(u, v) in m.iter() {
+        assert!(edges.contains(&(u, v)));
+        assert!(!used_r[v]);
+        used_r[v] = true;
+    }
+
+    // compare with max flow
+    let mut seed = 1_u64;
+    for _ in 0..20 {
+        let (left, right) = (30, 25);
+        let mut edges = vec![];
+        for u in 0..left {
+            for v in 0..right {
+                if rng(&mut seed).is_multiple_of(16) {
+                    edges.push((u, v));
+                }
+            }
+        }
+        let mut mf = crate::flow::MaxFlow::<i32>::new(left + right + 2);
+        let (s, t) = (left + right, left + right + 1);
+        for u in 0..left {
+            mf.add_edge(s, u, 1);
+        }
+        for v in 0..right {
+            mf.add_edge(left + v, t, 1);
+        }
+        for &(u, v) in edges.iter() {
+            mf.add_edge(u, left + v, 1);
+        }
+        assert_eq!(
+            bipartite_matching(left, right, &edges).len(),
+            mf.flow(s, t) as usize
+        );
+    }
+}
+
+#[test]
+fn test_markers() {
+    use proconio::input;
+    use proconio::source::once::OnceSource;
+
+    let src = "3 3\n1 2\n2 3\n3 1\n";
+    input! {
+        from OnceSource::from(src),
+        g: ListDiGraph,
+    }
+    assert_eq!(g, vec![vec![1], vec![2], vec![0]]);
+
+    input! {
+        from OnceSource::from("3 2\n0 2\n1 0\n"),
+        g: ListDiGraph<usize>,
+    }
+    assert_eq!(g, vec![vec![2], vec![0], vec![]]);
+
+    let src = "3 2\n1 2 10\n2 3 -5\n";
+    input! {
+        from OnceSource::from(src),
+        g: WeightedListGraph,
+    }
+    assert_eq!(
+        g,
+        vec![vec![(1, 10)], vec![(0, 10), (2, -5)], vec![(1, -5)]]
+    );
+
+    // `input!` can not parse types with multiple parameters
+    type G = WeightedListDiGraph<Usize1, i32>;
+    input! {
+        from OnceSource::from(src),
+        g: G,
+    }
+    assert_eq!(g, vec![vec![(1, 10)], vec![(2, -5)], vec![]]);
+
+    input! {
+        from OnceSource::from("4\n1 1 2\n"),
+        g: ParentTree,
+    }
+    assert_eq!(g, vec![vec![1, 2], vec![0, 3], vec![0], vec![1]]);
+
+    input! {
+        from OnceSource::from("3 2\n1 2\n1 2\n"),
+        (n, edges): EdgeList,
+    }
+    assert_eq!(n, 3);
+    assert_eq!(edges, vec![(0, 1), (0, 1)]);
+}
+
+#[test]
+fn test_board_graph() {
+    let board = Board::new(
+        ["S.#.", ".##.", "...G", "#.#."]
+            .iter()
+            .map(|r| r.chars().collect())
+            .collect(),
+    );
+    let s = board.find('S').unwrap();
+    let t = board.find('G').unwrap();
+
+    let g = BoardGraph::new(&board, |&c| c != '#');
+    let dist = make_dist_table(&g, s);
+    assert_eq!(dist[g.index(t)], NonInf(5));
+    assert_eq!(dist[g.index(board.ix(3, 0))], NonInf(7));
+    assert_eq!(dist[g.index(board.ix(2, 0))], Inf);
+    assert_eq!(dist[g.index(board.ix(1, 3))], NonInf(4));
+    assert_eq!(bfs(&g, s).count(), 10);
+
+    let g = BoardGraph::with_diagonal(&board, |&c| c != '#');
+    let dist = make_dist_table(&g, s);
+    assert_eq!(dist[g.index(t)], NonInf(4));
+    assert_eq!(dist[g.index(board.ix(3, 0))], NonInf(5));
+}
+
+#[test]
+fn test_bfs01() {
+    let g = make_weighted_directed_graph(
+        5,
+        &[
+            (0, 1, 1_u8),
+            (0, 2, 0),
+            (2, 1, 0),
+            (1, 3, 1),
+            (2, 3, 1),
+            (3, 0, 0),
+        ],
+    );
+    let (dist, prev) = bfs01(&g, 0);
+    assert_eq!(dist, vec![NonInf(0), NonInf(0), NonInf(0), NonInf(1), Inf]);
+    assert_eq!(restore_path(&prev, 1), vec![0, 2, 1]);
+
+    // number of walls to break
+    let board = Board::new(
+        ["S.#..", "###.#", "..#.G"]
+            .iter()
+            .map(|r| r.chars().collect::<Vec<_>>())
+            .collect(),
+    );
+    let s = board.find('S').unwrap();
+    let t = board.find('G').unwrap();
+    let g = BoardGraph::new(&board, |_| true);
+    let (dist, prev) = bfs01_with(&g, s, |_, v| if board[v] == '#' { 1 } else { 0 });
+    assert_eq!(dist[g.index(t)], NonInf(1));
+    assert_eq!(dist[g.index(board.ix(0, 2))], NonInf(1));
+    assert_eq!(dist[g.index(board.ix(3, 0))], NonInf(1));
+
+    let mut path = vec![t];
+    while let Some(p) = prev[g.index(*path.last().unwrap())] {
+        path.push(p);
+    }
+    assert_eq!(path.last(), Some(&s));
+    assert_eq!(path.iter().filter(|&&p| board[p] == '#').count(), 1);
+
+    // agrees with `make_dist_table` when every cost is 1
+    let g = BoardGraph::new(&board, |&c| c != '#');
+    assert_eq!(bfs01_with(&g, s, |_, _| 1).0, make_dist_table(&g, s));
+
+    // 8 neighbors
+    let board = Board::new(
+        ["S#.", "#.#", "..G"]
+            .iter()
+            .map(|r| r.chars().collect::<Vec<_>>())
+            .collect(),
+    );
+    let s = board.find('S').unwrap();
+    let t = board.find('G').unwrap();
+    let g = BoardGraph::with_diagonal(&board, |&c| c != '#');
+    let (dist, _) = bfs01_with(&g, s, |_, _| 1);
+    assert_eq!(dist, make_dist_table(&g, s));
+    assert_eq!(dist[g.index(t)], NonInf(2));
+    let g = BoardGraph::new(&board, |&c| c != '#');
+    assert_eq!(bfs01_with(&g, s, |_, _| 1).0[g.index(t)], Inf);
+}
+
+#[test]
+fn test_lowlink() {
+    use crate::test_util::rng;
+
+    //  0 - 1 - 2 - 5 = 6
+    //   \ /    |
+    //    3     4 - 7
+    let g = make_undirected_graph(
+        9,
+        &[
+            (0, 1),
+            (1, 3),
+            (3, 0),
+            (1, 2),
+            (2, 4),
+            (2, 5),
+            (5, 6),
+            (6, 5),
+            (4, 7),
+            (8, 8),
+        ],
+    );
+    let (bridges, articulations) = lowlink(&g);
+    assert_eq!(bridges, vec![(1, 2), (2, 4), (2, 5), (4, 7)]);
+    assert_eq!(articulations, vec![1, 2, 4, 5]);
+
+    let mut tecc = two_edge_connected_components(&g);
+    for c in tecc.iter_mut() {
+        c.sort_unstable();
+    }
+    tecc.sort();
+    assert_eq!(
+        tecc,
+        vec![
+            vec![0, 1, 3],
+            vec![2],
+            vec![4],
+            vec![5, 6],
+            vec![7],
+            vec![8]
+        ]
+    );
+
+    let mut bcc = biconnected_components(&g);
+    for c in bcc.iter_mut() {
+        c.sort_unstable();
+    }
+    bcc.sort();
+    assert_eq!(
+        bcc,
+        vec![
+            vec![0, 1, 3],
+            vec![1, 2],
+            vec![2, 4],
+            vec![2, 5],
+            vec![4, 7],
+            vec![5, 6],
+            vec![8]
+        ]
+    );
+
+    let (bct, k) = block_cut_tree(&g);
+    assert_eq!(k, 7);
+    assert_eq!(bct.len(), 16);
+    assert_eq!(bct[2].len(), 3);
+    assert_eq!(bct[8].len(), 1);
+
+    // a deep path must not overflow the stack
+    let n = 200_000;
+    let edges = (1..n).map(|i| (i - 1, i)).collect::<Vec<_>>();
+    let (bridges, articulations) = lowlink(&make_undirected_graph(n, &edges));
+    assert_eq!(bridges, edges);
+    assert_eq!(articulations.len(), n - 2);
+
+    fn components(n: usize, edges: &[(usize, usize)], removed: usize) -> usize {
+        let mut uf = UnionFind::new(n);
+        for &(u, v) in edges.iter() {
+            if u != removed && v != removed {
+                uf.union(u, v);
+            }
+        }
+        uf.count() - (removed < n) as usize
+    }
+
+    let mut seed = 1_u64;
+    let mut rand = |m: usize| rng(&mut seed) as usize % m;
+    for _ in 0..200 {
+        let n = rand(8) + 1;
+        let m = rand(12);
+        let edges = (0..m).map(|_| (rand(n), rand(n))).collect::<Vec<_>>();
+        let g = make_undirected_graph(n, &edges);
+        let (bridges, articulations) = lowlink(&g);
+        let base = components(n, &edges, n);
+
+        let mut expected = (0..m)
+            .filter(|&i| {
+                let rest = [&edges[..i], &edges[i + 1..]].concat();
+                components(n, &rest, n) > base
+            })
+            .map(|i| (min(edges[i].0, edges[i].1), max(edges[i].0, edges[i].1)))
+            .collect::<Vec<_>>();
+        expected.sort_unstable();
+        assert_eq!(bridges, expected);
+
+        let expected = (0..n)
+            .filter(|&v| components(n, &edges, v) > base)
+            .collect::<Vec<_>>();
+        assert_eq!(articulations, expected);
+
+        let rest = edges
+            .iter()
+            .filter(|&&(u, v)| bridges.binary_search(&(min(u, v), max(u, v))).is_err())
+            .cloned()
+            .collect::<Vec<_>>();
+        let mut uf = UnionFind::new(n);
+        for &(u, v) in rest.iter() {
+            uf.union(u, v);
+        }
+        let tecc = two_edge_connected_components(&g);
+        assert_eq!(tecc.len(), uf.count());
+        for c in tecc.iter() {
+            assert!(c.iter().all(|&v| uf.same(c[0], v)));
+        }
+
+        let bcc = biconnected_components(&g);
+        let mut count = vec![0; n];
+        for c in bcc.iter() {
+            for &v in c.iter() {
+                count[v] += 1;
+            }
+            // no single node disconnects a component
+            for &x in c.iter() {
+                let sub = edges
+                    .iter()
+                    .filter(|&&(u, v)| c.contains(&u) && c.contains(&v))
+                    .cloned()
+                    .collect::<Vec<_>>();
+                let mut uf = UnionFind::new(n);
+                for &(u, v) in sub.iter() {
+                    if u != x && v != x {
+                        uf.union(u, v);
+                    }
+                }
+                assert!(c
+                    .iter()
+                    .all(|&v| v == x || uf.same(v, c[(c[0] == x) as usize])));
+            }
+        }
+        for (v, &c) in count.iter().enumerate() {
+            assert_eq!(c > 1, articulations.contains(&v));
+        }
+        for &(u, v) in edges.iter() {
+            assert!(bcc.iter().any(|c| c.contains(&u) && c.contains(&v)));
+        }
+    }
+}
+
+#[test]
+fn test_eulerian_path() {
+    use crate::test_util::rng;
+
+    let g = make_directed_graph(4, &[(0, 1), (1, 2), (2, 0), (0, 3)]);
+    assert_eq!(eulerian_path(&g, true), Some(vec![0, 1, 2, 0, 3]));
+    let g = make_directed_graph(3, &[(0, 1), (0, 2)]);
+    assert_eq!(eulerian_path(&g, true), None);
+    // disconnected
+    let g = make_undirected_graph(4, &[(0, 1), (2, 3)]);
+    assert_eq!(eulerian_path(&g, false), None);
+    let g = make_undirected_graph(3, &[]);
+    assert_eq!(eulerian_path(&g, false), Some(vec![0]));
+    // multi-edges and a self-loop
+    let edges = [(0, 1), (1, 0), (1, 1), (1, 2)];
+    let g = make_undirected_graph(3, &edges);
+    let path = eulerian_path(&g, false).unwrap();
+    assert_eq!(path.len(), 5);
+    assert_eq!(path[4], 1 + 2 - path[0]);
+    let trail = eulerian_trail(3, &edges, false).unwrap();
+    let mut sorted = trail.clone();
+    sorted.sort_unstable();
+    assert_eq!(sorted, vec![0, 1, 2, 3]);
+
+    // a long cycle must not overflow the stack
+    let n = 200_000;
+    let edges = (0..n).map(|i| (i, (i + 1) % n)).collect::<Vec<_>>();
+    let trail = eulerian_trail(n, &edges, true).unwrap();
+    assert_eq!(trail, (0..n).collect::<Vec<_>>());
+
+    // exhaustive search over orders of edges
+    fn exists(
+        u: usize,
+        edges: &[(usize, usize)],
+        used: &mut [bool],
+        rest: usize,
+        directed: bool,
+    ) -> bool {
+        if rest == 0 {
+            return true;
+        }
+        for i in 0..edges.len() {
+            let (a, b) = edges[i];
+            if used[i] {
+                continue;
+            }
+            let v = if a == u {
+                b
+            } else if !directed && b == u {
+                a
+            } else {
+                continue;
+            };
+            used[i] = true;
+            let found = exists(v, edges, used, rest - 1, directed);
+            used[i] = false;
+            if found {
+                return true;
+            }
+        }
+        false
+    }
+
+    let mut seed = 1_u64;
+    let mut rand = |m: usize| rng(&mut seed) as usize % m;
+    for _ in 0..300 {
+        let n = rand(4) + 1;
+        let m = rand(7) + 1;
+        let edges = (0..m).map(|_| (rand(n), rand(n))).collect::<Vec<_>>();
+        for &directed in [false, true].iter() {
+            let expected = (0..n).any(|s| exists(s, &edges, &mut vec![false; m], m, directed));
+            let trail = eulerian_trail(n, &edges, directed);
+            assert_eq!(trail.is_some(), expected);
+            let g = if directed {
+                make_directed_graph(n, &edges)
+            } else {
+                make_undirected_graph(n, &edges)
+            };
+            let path = eulerian_path(&g, directed);
+            assert_eq!(path.is_some(), expected);
+
+            if let Some(trail) = trail {
+                let mut sorted = trail.clone();
+                sorted.sort_unstable();
+                assert_eq!(sorted, (0..m).collect::<Vec<_>>());
+                let walk = |mut u: usize| {
+                    for &i in trail.iter() {
+                        let (a, b) = edges[i];
+                        u = if a == u {
+                            b
+                        } else if !directed && b == u {
+                            a
+                        } else {
+                            return false;
+                        };
+                    }
+                    true
+                };
+                let (a, b) = edges[trail[0]];
+                assert!(walk(a) || !directed && walk(b));
+            }
+            if let Some(path) = path {
+                assert_eq!(path.len(), m + 1);
+                let mut used = vec![false; m];
+                for w in path.windows(2) {
+                    let i = (0..m)
+                        .find(|&i| {
+                            !used[i]
+                                && (edges[i] == (w[0], w[1])
+                                    || !directed && edges[i] == (w[1], w[0]))
+                        })
+                        .unwrap();
+                    used[i] = true;
+                }
+            }
+        }
+    }
+}
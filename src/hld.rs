@@ -0,0 +1,267 @@
+use crate::monoid::Monoid;
+use crate::segment_tree::SegmentTree;
+use crate::tree::traverse;
+
+/// Heavy-light decomposition
+///
+/// Holds a value of monoid `T` on each node, and answers path and subtree queries.
+/// Non-commutative monoids are fine: `path_query(u, v)` folds values in order from `u` to `v`.
+#[derive(Debug)]
+pub struct Hld<T> {
+    par: Vec<usize>,
+    depth: Vec<usize>,
+    size: Vec<usize>,
+    head: Vec<usize>,
+    pos: Vec<usize>,
+    // values in the order of `pos`
+    st: SegmentTree<T>,
+    // values in the reversed order of `pos`
+    rev: SegmentTree<T>,
+}
+
+impl<T: Clone + Monoid> Hld<T> {
+    /// O(n).
+    /// Decompose the tree `g` rooted at `root`. All values are `T::mempty()`.
+    pub fn new(g: &[Vec<usize>], root: usize) -> Self {
+        let s: &[T] = &[];
+        Self::init(g, root, s)
+    }
+
+    /// O(n).
+    /// Decompose the tree `g` rooted at `root`, and put `vals[v]` on each node `v`.
+    pub fn from_values(g: &[Vec<usize>], root: usize, vals: &[impl Into<T> + Clone]) -> Self {
+        assert_eq!(g.len(), vals.len());
+        Self::init(g, root, vals)
+    }
+
+    fn init(g: &[Vec<usize>], root: usize, vals: &[impl Into<T> + Clone]) -> Self {
+        let n = g.len();
+        let (par, depth, order) = traverse(g, root);
+        let par = par
+            .into_iter()
+            .map(|p| p.unwrap_or(usize::MAX))
+            .collect::<Vec<_>>();
+
+        let mut size = vec![1; n];
+        let mut heavy = vec![usize::MAX; n];
+        for &u in order.iter().rev() {
+            if u != root {
+                let p = par[u];
+                size[p] += size[u];
+                if heavy[p] == usize::MAX || size[heavy[p]] < size[u] {
+                    heavy[p] = u;
+                }
+            }
+        }
+
+        // DFS visiting the heavy child first, so that both heavy paths
+        // and subtrees occupy contiguous positions
+        let mut head = vec![root; n];
+        let mut pos = vec![0; n];
+        let mut cur = 0;
+        let mut stack = vec![root];
+        while let Some(u) = stack.pop() {
+            pos[u] = cur;
+            cur += 1;
+            for &v in g[u].iter() {
+                if v != par[u] && v != heavy[u] {
+                    head[v] = v;
+                    stack.push(v);
+                }
+            }
+            if heavy[u] != usize::MAX {
+                head[heavy[u]] = head[u];
+                stack.push(heavy[u]);
+            }
+        }
+
+        let mut st = SegmentTree::new(n);
+        let mut rev = SegmentTree::new(n);
+        if !vals.is_empty() {
+            let mut xs = vec![T::mempty(); n];
+            for u in 0..n {
+                xs[pos[u]] = vals[u].clone().into();
+            }
+            st = SegmentTree::from_slice(&xs);
+            xs.reverse();
+            rev = SegmentTree::from_slice(&xs);
+        }
+
+        Self {
+            par,
+            depth,
+            size,
+            head,
+            pos,
+            st,
+            rev,
+        }
+    }
+
+    /// Position of `v` in the underlying segment tree.
+    pub fn index(&self, v: usize) -> usize {
+        self.pos[v]
+    }
+
+    /// O(log n).
+    /// Set `x` to the node `v`.
+    pub fn set(&mut self, v: usize, x: impl Into<T>) {
+        let x = x.into();
+        let n = self.pos.len();
+        self.rev.set(n - 1 - self.pos[v], x.clone());
+        self.st.set(self.pos[v], x);
+    }
+
+    /// O(1).
+    /// Get the value of the node `v`.
+    pub fn get(&self, v: usize) -> T {
+        self.st.get(self.pos[v])
+    }
+
+    /// O(log n).
+    /// Lowest common ancestor of `u` and `v`.
+    pub fn lca(&self, u: usize, v: usize) -> usize {
+        let mut u = u;
+        let mut v = v;
+        while self.head[u] != self.head[v] {
+            if self.depth[self.head[u]] >= self.depth[self.head[v]] {
+                u = self.par[self.head[u]];
+            } else {
+                v = self.par[self.head[v]];
+            }
+        }
+        if self.depth[u] <= self.depth[v] {
+            u
+        } else {
+            v
+        }
+    }
+
+    // fold of positions `r..=l` in decreasing order
+    fn query_rev(&self, l: usize, r: usize) -> T {
+        let n = self.pos.len();
+        self.rev.query(n - 1 - r..=n - 1 - l)
+    }
+
+    /// O(log^2 n).
+    /// Fold values on the path from `u` to `v`, both inclusive, in this order.
+    pub fn path_query(&self, u: usize, v: usize) -> T {
+        let mut u = u;
+        let mut v = v;
+        // fold of the path from the original `u` up to `u` (exclusive)
+        let mut up = T::mempty();
+        // fold of the path from `v` (exclusive) down to the original `v`
+        let mut down = T::mempty();
+
+        while self.head[u] != self.head[v] {
+            if self.depth[self.head[u]] >= self.depth[self.head[v]] {
+                let h = self.head[u];
+                up = T::mappend(&up, &self.query_rev(self.pos[h], self.pos[u]));
+                u = self.par[h];
+            } else {
+                let h = self.head[v];
+                down = T::mappend(&self.st.query(self.pos[h]..=self.pos[v]), &down);
+                v = self.par[h];
+            }
+        }
+
+        if self.pos[u] >= self.pos[v] {
+            up = T::mappend(&up, &self.query_rev(self.pos[v], self.pos[u]));
+        } else {
+            down = T::mappend(&self.st.query(self.pos[u]..=self.pos[v]), &down);
+        }
+        T::mappend(&up, &down)
+    }
+
+    /// O(log n).
+    /// Fold values in the subtree of `v` (in the order of `index`).
+    pub fn subtree_query(&self, v: usize) -> T {
+        self.st.query(self.pos[v]..self.pos[v] + self.size[v])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::graph::make_undirected_graph;
+    use crate::monoid::{Max, Sum};
+    use crate::tree::Tree;
+
+    // Non-commutative monoid which records the visiting order
+    #[derive(Clone, Debug, PartialEq)]
+    struct Seq(Vec<usize>);
+
+    impl Monoid for Seq {
+        fn mempty() -> Self {
+            Seq(vec![])
+        }
+
+        fn mappend(l: &Self, r: &Self) -> Self {
+            Seq(l.0.iter().chain(r.0.iter()).cloned().collect())
+        }
+    }
+
+    fn sample() -> Vec<Vec<usize>> {
+        make_undirected_graph(
+            10,
+            &[
+                (0, 1),
+                (0, 2),
+                (1, 3),
+                (1, 4),
+                (4, 5),
+                (4, 6),
+                (2, 7),
+                (7, 8),
+                (3, 9),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_path_order() {
+        let g = sample();
+        let t = Tree::new(&g, 0);
+        let vals = (0..g.len()).map(|v| Seq(vec![v])).collect::<Vec<_>>();
+        let hld = Hld::<Seq>::from_values(&g, 0, &vals);
+
+        for u in 0..g.len() {
+            for v in 0..g.len() {
+                assert_eq!(hld.path_query(u, v).0, t.path(u, v));
+                assert_eq!(hld.lca(u, v), t.lca(u, v));
+            }
+            let mut sub = hld.subtree_query(u).0;
+            sub.sort_unstable();
+            let mut expected = t.preorder()[t.tin(u)..t.tout(u)].to_vec();
+            expected.sort_unstable();
+            assert_eq!(sub, expected);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "graph is not a tree")]
+    fn test_cycle() {
+        let g = make_undirected_graph(3, &[(0, 1), (1, 2), (2, 0)]);
+        Hld::<Sum<i64>>::new(&g, 0);
+    }
+
+    #[test]
+    fn test_update() {
+        let g = sample();
+        let mut sum = Hld::<Sum<i64>>::new(&g, 3);
+        let mut max = Hld::<Max<i64>>::from_values(&g, 3, &[0; 10]);
+        for v in 0..g.len() {
+            sum.set(v, v as i64);
+            max.set(v, v as i64);
+        }
+        assert_eq!(sum.get(5).0, 5);
+        assert_eq!(sum.path_query(9, 8).0, 9 + 3 + 1 + 2 + 7 + 8);
+        assert_eq!(max.path_query(5, 6).0, 6);
+        assert_eq!(max.path_query(5, 3).0, 5);
+        assert_eq!(sum.subtree_query(1).0, 1 + 4 + 5 + 6 + 2 + 7 + 8);
+        assert_eq!(sum.subtree_query(3).0, 45);
+
+        sum.set(1, 100);
+        assert_eq!(sum.path_query(6, 2).0, 6 + 4 + 100 + 2);
+    }
+}
@@ -0,0 +1,434 @@
+use crate::monoid::{Max, Min, Monoid, Sum};
+use crate::segment_tree::to_half_open;
+use num::{Bounded, One, Zero};
+use std::convert::TryFrom;
+use std::ops::{Add, Mul, RangeBounds};
+
+/// A trait of monoid actions
+///
+/// A monoid of operators which acts on monoid `T`.
+/// `len` is the number of elements which are folded into `x`.
+/// Instances should satisfy the following laws:
+/// * `act(MEMPTY, x, len) = x`
+/// * `act(mappend(f, g), x, len) = act(f, act(g, x, len), len)`
+/// * `act(f, mappend(x, y), lx + ly) = mappend(act(f, x, lx), act(f, y, ly))`
+///
+pub trait Action<T: Monoid>: Monoid {
+    /// Apply operator `f` to `x`
+    fn act(f: &Self, x: &T, len: usize) -> T;
+}
+
+fn from_len<T: TryFrom<usize>>(len: usize) -> T {
+    T::try_from(len).ok().unwrap()
+}
+
+/// Add a value to every element: `x -> x + a`
+#[derive(Clone, Copy, Debug)]
+pub struct RangeAdd<T>(pub T);
+
+impl<T: Copy + Zero + Add<Output = T>> Monoid for RangeAdd<T> {
+    fn mempty() -> Self {
+        Self(T::zero())
+    }
+
+    fn mappend(l: &Self, r: &Self) -> Self {
+        Self(l.0 + r.0)
+    }
+}
+
+impl<T> From<T> for RangeAdd<T> {
+    fn from(v: T) -> Self {
+        RangeAdd(v)
+    }
+}
+
+impl<T: Copy + Zero + Add<Output = T> + Mul<Output = T> + TryFrom<usize>> Action<Sum<T>>
+    for RangeAdd<T>
+{
+    fn act(f: &Self, x: &Sum<T>, len: usize) -> Sum<T> {
+        Sum(x.0 + f.0 * from_len(len))
+    }
+}
+
+impl<T: Copy + Ord + Bounded + Zero + Add<Output = T>> Action<Min<T>> for RangeAdd<T> {
+    fn act(f: &Self, x: &Min<T>, _len: usize) -> Min<T> {
+        // Keep the identity as is, it stands for an empty range
+        if x.0 == T::max_value() {
+            *x
+        } else {
+            Min(x.0 + f.0)
+        }
+    }
+}
+
+impl<T: Copy + Ord + Bounded + Zero + Add<Output = T>> Action<Max<T>> for RangeAdd<T> {
+    fn act(f: &Self, x: &Max<T>, _len: usize) -> Max<T> {
+        // Keep the identity as is, it stands for an empty range
+        if x.0 == T::min_value() {
+            *x
+        } else {
+            Max(x.0 + f.0)
+        }
+    }
+}
+
+/// Overwrite every element: `x -> a`
+///
+/// `RangeAssign(None)` is the identity.
+#[derive(Clone, Copy, Debug)]
+pub struct RangeAssign<T>(pub Option<T>);
+
+impl<T: Copy> Monoid for RangeAssign<T> {
+    fn mempty() -> Self {
+        Self(None)
+    }
+
+    fn mappend(l: &Self, r: &Self) -> Self {
+        Self(l.0.or(r.0))
+    }
+}
+
+impl<T> From<T> for RangeAssign<T> {
+    fn from(v: T) -> Self {
+        RangeAssign(Some(v))
+    }
+}
+
+impl<T: Copy + Zero + Add<Output = T> + Mul<Output = T> + TryFrom<usize>> Action<Sum<T>>
+    for RangeAssign<T>
+{
+    fn act(f: &Self, x: &Sum<T>, len: usize) -> Sum<T> {
+        f.0.map_or(*x, |a| Sum(a * from_len(len)))
+    }
+}
+
+impl<T: Copy + Ord + Bounded> Action<Min<T>> for RangeAssign<T> {
+    fn act(f: &Self, x: &Min<T>, _len: usize) -> Min<T> {
+        f.0.map_or(*x, Min)
+    }
+}
+
+impl<T: Copy + Ord + Bounded> Action<Max<T>> for RangeAssign<T> {
+    fn act(f: &Self, x: &Max<T>, _len: usize) -> Max<T> {
+        f.0.map_or(*x, Max)
+    }
+}
+
+/// Affine transformation of every element: `x -> a * x + b`
+#[derive(Clone, Copy, Debug)]
+pub struct RangeAffine<T>(pub T, pub T);
+
+impl<T: Copy + Zero + One + Add<Output = T> + Mul<Output = T>> Monoid for RangeAffine<T> {
+    fn mempty() -> Self {
+        Self(T::one(), T::zero())
+    }
+
+    fn mappend(l: &Self, r: &Self) -> Self {
+        Self(l.0 * r.0, l.0 * r.1 + l.1)
+    }
+}
+
+impl<T> From<(T, T)> for RangeAffine<T> {
+    fn from(v: (T, T)) -> Self {
+        RangeAffine(v.0, v.1)
+    }
+}
+
+impl<T: Copy + Zero + One + Add<Output = T> + Mul<Output = T> + TryFrom<usize>> Action<Sum<T>>
+    for RangeAffine<T>
+{
+    fn act(f: &Self, x: &Sum<T>, len: usize) -> Sum<T> {
+        Sum(f.0 * x.0 + f.1 * from_len(len))
+    }
+}
+
+/// Segment tree with lazy propagation
+///
+/// Supports range update by operators `F` and range query of monoid `T`.
+#[derive(Debug)]
+pub struct LazySegmentTree<T, F> {
+    len: usize,
+    log: usize,
+    v: Vec<T>,
+    lz: Vec<F>,
+}
+
+impl<T: Clone + Monoid, F: Clone + Action<T>> LazySegmentTree<T, F> {
+    /// O(n).
+    /// Construct lazy segment tree for given size.
+    pub fn new(n: usize) -> Self {
+        let s: &[T] = &[];
+        Self::init(n, s)
+    }
+
+    /// O(n).
+    /// Construct lazy segment tree from slice.
+    pub fn from_slice(s: &[impl Into<T> + Clone]) -> Self {
+        Self::init(s.len(), s)
+    }
+
+    fn init(len: usize, s: &[impl Into<T> + Clone]) -> Self {
+        let n = len.next_power_of_two();
+        let log = n.trailing_zeros() as usize;
+        let mut v = vec![T::mempty(); n * 2];
+        for (i, x) in s.iter().enumerate() {
+            v[n + i] = x.clone().into();
+        }
+        let lz = vec![F::mempty(); n];
+
+        let mut ret = Self { len, log, v, lz };
+        for k in (1..n).rev() {
+            ret.update(k);
+        }
+        ret
+    }
+
+    /// O(1).
+    /// Length of sequence.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// O(1).
+    /// Returns true if the sequence is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// O(log n).
+    /// Set v to `i`-th element.
+    /// `s[i] = v`
+    pub fn set(&mut self, i: usize, v: impl Into<T>) {
+        assert!(i < self.len);
+        let k = self.lz.len() + i;
+        for j in (1..=self.log).rev() {
+            self.push(k >> j);
+        }
+        self.v[k] = v.into();
+        for j in 1..=self.log {
+            self.update(k >> j);
+        }
+    }
+
+    /// O(log n).
+    /// Get i-th element
+    /// Equals to `query(i..=i)`
+    pub fn get(&mut self, i: usize) -> T {
+        assert!(i < self.len);
+        let k = self.lz.len() + i;
+        for j in (1..=self.log).rev() {
+            self.push(k >> j);
+        }
+        self.v[k].clone()
+    }
+
+    /// O(log n).
+    /// Query for `range`.
+    /// Returns `T::mconcat(&s[range])`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use competitive::monoid::Sum;
+    /// # use competitive::lazy_segment_tree::{LazySegmentTree, RangeAdd};
+    /// let mut st = LazySegmentTree::<Sum<i64>, RangeAdd<i64>>::from_slice(&[1, 2, 3, 4, 5]);
+    /// st.apply(1..4, 10);
+    /// assert_eq!(st.query(0..=2).0, 26);
+    /// assert_eq!(st.query(4..).0, 5);
+    /// ```
+    ///
+    pub fn query(&mut self, range: impl RangeBounds<usize>) -> T {
+        let (l, r) = to_half_open(range, self.len);
+        if l == r {
+            return T::mempty();
+        }
+
+        let n = self.lz.len();
+        let mut l = n + l;
+        let mut r = n + r;
+        self.push_boundary(l, r);
+
+        let mut ret_l = T::mempty();
+        let mut ret_r = T::mempty();
+        while l < r {
+            if l & 1 != 0 {
+                ret_l = T::mappend(&ret_l, &self.v[l]);
+                l += 1;
+            }
+            if r & 1 != 0 {
+                r -= 1;
+                ret_r = T::mappend(&self.v[r], &ret_r);
+            }
+            l /= 2;
+            r /= 2;
+        }
+
+        T::mappend(&ret_l, &ret_r)
+    }
+
+    /// O(log n).
+    /// Apply operator `f` to every element in `range`.
+    /// `s[i] = act(f, s[i])` for `i` in `range`
+    pub fn apply(&mut self, range: impl RangeBounds<usize>, f: impl Into<F>) {
+        let (l, r) = to_half_open(range, self.len);
+        if l == r {
+            return;
+        }
+        let f = f.into();
+
+        let n = self.lz.len();
+        let l = n + l;
+        let r = n + r;
+        self.push_boundary(l, r);
+
+        {
+            let mut l = l;
+            let mut r = r;
+            while l < r {
+                if l & 1 != 0 {
+                    self.all_apply(l, &f);
+                    l += 1;
+                }
+                if r & 1 != 0 {
+                    r -= 1;
+                    self.all_apply(r, &f);
+                }
+                l /= 2;
+                r /= 2;
+            }
+        }
+
+        for i in 1..=self.log {
+            if ((l >> i) << i) != l {
+                self.update(l >> i);
+            }
+            if ((r >> i) << i) != r {
+                self.update((r - 1) >> i);
+            }
+        }
+    }
+
+    fn push_boundary(&mut self, l: usize, r: usize) {
+        for i in (1..=self.log).rev() {
+            if ((l >> i) << i) != l {
+                self.push(l >> i);
+            }
+            if ((r >> i) << i) != r {
+                self.push((r - 1) >> i);
+            }
+        }
+    }
+
+    // Number of leaves under the node `k`
+    fn node_len(&self, k: usize) -> usize {
+        self.lz.len() >> (usize::BITS - 1 - k.leading_zeros())
+    }
+
+    fn update(&mut self, k: usize) {
+        self.v[k] = T::mappend(&self.v[k * 2], &self.v[k * 2 + 1]);
+    }
+
+    fn all_apply(&mut self, k: usize, f: &F) {
+        self.v[k] = F::act(f, &self.v[k], self.node_len(k));
+        if k < self.lz.len() {
+            self.lz[k] = F::mappend(f, &self.lz[k]);
+        }
+    }
+
+    fn push(&mut self, k: usize) {
+        let f = std::mem::replace(&mut self.lz[k], F::mempty());
+        self.all_apply(k * 2, &f);
+        self.all_apply(k * 2 + 1, &f);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_util::rng;
+
+    #[test]
+    fn test_add_sum() {
+        let mut v = [0_i64; 13];
+        let mut st = LazySegmentTree::<Sum<i64>, RangeAdd<i64>>::new(v.len());
+        let mut seed = 1;
+        for _ in 0..1000 {
+            let a = rng(&mut seed) as usize % (v.len() + 1);
+            let b = rng(&mut seed) as usize % (v.len() + 1);
+            let (l, r) = (a.min(b), a.max(b));
+            if rng(&mut seed).is_multiple_of(2) {
+                let x = rng(&mut seed) as i64 % 100 - 50;
+                st.apply(l..r, x);
+                v[l..r].iter_mut().for_each(|e| *e += x);
+            } else {
+                assert_eq!(st.query(l..r).0, v[l..r].iter().sum::<i64>());
+            }
+        }
+        for (i, &x) in v.iter().enumerate() {
+            assert_eq!(st.get(i).0, x);
+        }
+        assert_eq!(st.query(..).0, v.iter().sum::<i64>());
+    }
+
+    #[test]
+    fn test_assign_min() {
+        let mut v = [i64::MAX; 10];
+        let mut st = LazySegmentTree::<Min<i64>, RangeAssign<i64>>::new(v.len());
+        let mut seed = 2;
+        for _ in 0..1000 {
+            let a = rng(&mut seed) as usize % (v.len() + 1);
+            let b = rng(&mut seed) as usize % (v.len() + 1);
+            let (l, r) = (a.min(b), a.max(b));
+            match rng(&mut seed) % 3 {
+                0 => {
+                    let x = rng(&mut seed) as i64 % 100;
+                    st.apply(l..r, x);
+                    v[l..r].iter_mut().for_each(|e| *e = x);
+                }
+                1 if l < v.len() => {
+                    let x = rng(&mut seed) as i64 % 100;
+                    st.set(l, x);
+                    v[l] = x;
+                }
+                _ => {
+                    let expected = v[l..r].iter().cloned().min().unwrap_or(i64::MAX);
+                    assert_eq!(st.query(l..r).0, expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_add_min() {
+        let mut st = LazySegmentTree::<Min<i32>, RangeAdd<i32>>::from_slice(&[5, 3, 8, 1, 4]);
+        st.apply(2..=3, 10);
+        assert_eq!(st.query(..).0, 3);
+        assert_eq!(st.query(2..4).0, 11);
+        st.apply(.., -3);
+        assert_eq!(st.query(..).0, 0);
+        assert_eq!(st.query(3..).0, 1);
+    }
+
+    #[test]
+    fn test_affine_gf() {
+        type GF = crate::gf::GF<998244353>;
+
+        let mut v: Vec<GF> = (1..=7).map(GF::new).collect();
+        let mut st = LazySegmentTree::<Sum<GF>, RangeAffine<GF>>::from_slice(&v);
+        let mut seed = 3;
+        for _ in 0..1000 {
+            let a = rng(&mut seed) as usize % (v.len() + 1);
+            let b = rng(&mut seed) as usize % (v.len() + 1);
+            let (l, r) = (a.min(b), a.max(b));
+            if rng(&mut seed).is_multiple_of(2) {
+                let c = GF::new(rng(&mut seed) as i64);
+                let d = GF::new(rng(&mut seed) as i64);
+                st.apply(l..r, (c, d));
+                v[l..r].iter_mut().for_each(|e| *e = c * *e + d);
+            } else {
+                let expected = v[l..r].iter().fold(GF::new(0), |acc, &e| acc + e);
+                assert_eq!(st.query(l..r).0, expected);
+            }
+        }
+    }
+}
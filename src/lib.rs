@@ -1,21 +1,30 @@
 pub mod binary_search;
 pub mod bits;
 pub mod collections;
+pub mod convolution;
 pub mod display;
+pub mod flow;
+pub mod fps;
 pub mod geo;
 pub mod gf;
 pub mod graph;
+pub mod hld;
 pub mod inf;
 pub mod io;
 pub mod ix;
 pub mod kmp;
+pub mod lazy_segment_tree;
 pub mod monoid;
 pub mod number;
 pub mod prime;
 pub mod range;
 pub mod segment_tree;
 pub mod slice;
+pub mod tree;
 pub mod union_find;
 
+#[cfg(test)]
+mod test_util;
+
 #[macro_use]
 pub mod prelude;
@@ -25,9 +25,11 @@ pub use crate::binary_search::{binary_search, lower_bound, upper_bound};
 pub use crate::bits::{power_bitset, SmallBitSet};
 pub use crate::collections::MultiSet;
 pub use crate::display::{AtCoder, Mat, Vertical};
+pub use crate::fps::Fps;
 pub use crate::gf::GF;
 pub use crate::inf::{MaybeInf, MaybeInf::*};
 pub use crate::ix::{Board, Ix2};
+pub use crate::lazy_segment_tree::{LazySegmentTree, RangeAdd, RangeAffine, RangeAssign};
 pub use crate::monoid::{Max, Min, Monoid, Product, Sum};
 pub use crate::range::RangeExt;
 pub use crate::segment_tree::SegmentTree;
@@ -94,19 +94,7 @@ impl<T: Clone + Monoid> SegmentTree<T> {
     /// ```
     ///
     pub fn query(&self, range: impl RangeBounds<usize>) -> T {
-        let l = match range.start_bound() {
-            Bound::Included(v) => *v,
-            Bound::Excluded(v) => v + 1,
-            Bound::Unbounded => 0,
-        };
-        let r = match range.end_bound() {
-            Bound::Included(v) => v + 1,
-            Bound::Excluded(v) => *v,
-            Bound::Unbounded => self.len,
-        };
-
-        assert!(l <= r);
-        assert!(r <= self.len);
+        let (l, r) = to_half_open(range, self.len);
 
         let n = (self.v.len() + 1) / 2;
         let mut l = n + l;
@@ -129,6 +117,125 @@ impl<T: Clone + Monoid> SegmentTree<T> {
 
         T::mappend(&ret_l, &ret_r)
     }
+
+    /// O(log n).
+    /// Returns the largest `r` such that `pred(query(l..r))` holds.
+    ///
+    /// `pred` must be monotone and `pred(T::mempty())` must be true.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use competitive::monoid::Sum;
+    /// # use competitive::segment_tree::SegmentTree;
+    /// let st = SegmentTree::<Sum<i64>>::from_slice(&[1, 2, 3, 4, 5]);
+    /// assert_eq!(st.max_right(0, |s| s.0 < 6), 2);
+    /// assert_eq!(st.max_right(1, |s| s.0 <= 9), 4);
+    /// ```
+    ///
+    pub fn max_right(&self, l: usize, pred: impl Fn(&T) -> bool) -> usize {
+        assert!(l <= self.len);
+        assert!(pred(&T::mempty()));
+        if l == self.len {
+            return self.len;
+        }
+
+        // `k` is 1-origin index of nodes, `self.v[k - 1]` is the node `k`
+        let n = self.v.len().div_ceil(2);
+        let mut k = n + l;
+        let mut acc = T::mempty();
+        loop {
+            while k & 1 == 0 {
+                k /= 2;
+            }
+            let next = T::mappend(&acc, &self.v[k - 1]);
+            if !pred(&next) {
+                while k < n {
+                    k *= 2;
+                    let next = T::mappend(&acc, &self.v[k - 1]);
+                    if pred(&next) {
+                        acc = next;
+                        k += 1;
+                    }
+                }
+                return k - n;
+            }
+            acc = next;
+            k += 1;
+            if k & k.wrapping_neg() == k {
+                return self.len;
+            }
+        }
+    }
+
+    /// O(log n).
+    /// Returns the smallest `l` such that `pred(query(l..r))` holds.
+    ///
+    /// `pred` must be monotone and `pred(T::mempty())` must be true.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use competitive::monoid::Sum;
+    /// # use competitive::segment_tree::SegmentTree;
+    /// let st = SegmentTree::<Sum<i64>>::from_slice(&[1, 2, 3, 4, 5]);
+    /// assert_eq!(st.min_left(5, |s| s.0 <= 9), 3);
+    /// assert_eq!(st.min_left(3, |s| s.0 < 100), 0);
+    /// ```
+    ///
+    pub fn min_left(&self, r: usize, pred: impl Fn(&T) -> bool) -> usize {
+        assert!(r <= self.len);
+        assert!(pred(&T::mempty()));
+        if r == 0 {
+            return 0;
+        }
+
+        // `k` is 1-origin index of nodes, `self.v[k - 1]` is the node `k`
+        let n = self.v.len().div_ceil(2);
+        let mut k = n + r;
+        let mut acc = T::mempty();
+        loop {
+            k -= 1;
+            while k > 1 && k & 1 == 1 {
+                k /= 2;
+            }
+            let next = T::mappend(&self.v[k - 1], &acc);
+            if !pred(&next) {
+                while k < n {
+                    k = k * 2 + 1;
+                    let next = T::mappend(&self.v[k - 1], &acc);
+                    if pred(&next) {
+                        acc = next;
+                        k -= 1;
+                    }
+                }
+                return k + 1 - n;
+            }
+            acc = next;
+            if k & k.wrapping_neg() == k {
+                return 0;
+            }
+        }
+    }
+}
+
+/// Converts `range` into a half-open interval `[l, r)` within `0..len`.
+pub(crate) fn to_half_open(range: impl RangeBounds<usize>, len: usize) -> (usize, usize) {
+    let l = match range.start_bound() {
+        Bound::Included(v) => *v,
+        Bound::Excluded(v) => v + 1,
+        Bound::Unbounded => 0,
+    };
+    let r = match range.end_bound() {
+        Bound::Included(v) => v + 1,
+        Bound::Excluded(v) => *v,
+        Bound::Unbounded => len,
+    };
+
+    assert!(l <= r);
+    assert!(r <= len);
+
+    (l, r)
 }
 
 #[test]
@@ -181,3 +288,51 @@ fn test() {
     assert_eq!(st.query(..3).0, 6);
     assert_eq!(st.query(..=3).0, 10);
 }
+
+#[test]
+fn test_max_right_min_left() {
+    use crate::monoid::{Max, Min, Sum};
+
+    let v = [3_i64, 1, 4, 1, 5, 9, 2, 6, 5];
+    let sum = SegmentTree::<Sum<i64>>::from_slice(&v);
+    let max = SegmentTree::<Max<i64>>::from_slice(&v);
+    let min = SegmentTree::<Min<i64>>::from_slice(&v);
+
+    // naive versions
+    let max_right = |l: usize, pred: &dyn Fn(&[i64]) -> bool| {
+        (l..=v.len()).rev().find(|&r| pred(&v[l..r])).unwrap()
+    };
+    let min_left =
+        |r: usize, pred: &dyn Fn(&[i64]) -> bool| (0..=r).find(|&l| pred(&v[l..r])).unwrap();
+
+    for i in 0..=v.len() {
+        for k in 0..=40 {
+            assert_eq!(
+                sum.max_right(i, |s| s.0 <= k),
+                max_right(i, &|s| s.iter().sum::<i64>() <= k)
+            );
+            assert_eq!(
+                sum.min_left(i, |s| s.0 <= k),
+                min_left(i, &|s| s.iter().sum::<i64>() <= k)
+            );
+        }
+        for k in 0..=10 {
+            assert_eq!(
+                max.max_right(i, |s| s.0 < k),
+                max_right(i, &|s| s.iter().all(|&x| x < k))
+            );
+            assert_eq!(
+                max.min_left(i, |s| s.0 < k),
+                min_left(i, &|s| s.iter().all(|&x| x < k))
+            );
+            assert_eq!(
+                min.max_right(i, |s| s.0 > k),
+                max_right(i, &|s| s.iter().all(|&x| x > k))
+            );
+            assert_eq!(
+                min.min_left(i, |s| s.0 > k),
+                min_left(i, &|s| s.iter().all(|&x| x > k))
+            );
+        }
+    }
+}
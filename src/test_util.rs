@@ -0,0 +1,17 @@
+//! Helpers shared by unit tests
+
+use crate::gf::GF;
+
+/// Simple linear congruential generator for reproducible tests.
+/// Returns 31 random bits.
+pub(crate) fn rng(seed: &mut u64) -> u64 {
+    *seed = seed
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    *seed >> 33
+}
+
+/// `n` random elements of `GF<P>`.
+pub(crate) fn random_gf<const P: u64>(n: usize, seed: &mut u64) -> Vec<GF<P>> {
+    (0..n).map(|_| GF(rng(seed) % P)).collect()
+}
@@ -0,0 +1,631 @@
+use crate::monoid::Monoid;
+use std::collections::VecDeque;
+
+/// Rooted tree
+///
+/// Built from an adjacency list such as the one `graph::ListTree` reads.
+/// All traversals are iterative, so deep trees are fine.
+#[derive(Debug, Clone)]
+pub struct Tree {
+    root: usize,
+    par: Vec<Option<usize>>,
+    depth: Vec<usize>,
+    size: Vec<usize>,
+    tin: Vec<usize>,
+    order: Vec<usize>,
+    // up[k][v] is the 2^k-th ancestor of v (root for overflows)
+    up: Vec<Vec<usize>>,
+}
+
+impl Tree {
+    /// O(n log n).
+    /// Construct a tree rooted at `root`.
+    pub fn new(g: &[Vec<usize>], root: usize) -> Self {
+        let n = g.len();
+        let (par, depth, order) = traverse(g, root);
+
+        let mut size = vec![1; n];
+        for &u in order.iter().rev() {
+            if let Some(p) = par[u] {
+                size[p] += size[u];
+            }
+        }
+
+        let mut tin = vec![0; n];
+        for (i, &u) in order.iter().enumerate() {
+            tin[u] = i;
+        }
+
+        let log = (usize::BITS - n.leading_zeros()).max(1) as usize;
+        let mut up = vec![(0..n).map(|u| par[u].unwrap_or(root)).collect::<Vec<_>>()];
+        for k in 1..log {
+            let prev = &up[k - 1];
+            let next = (0..n).map(|u| prev[prev[u]]).collect();
+            up.push(next);
+        }
+
+        Self {
+            root,
+            par,
+            depth,
+            size,
+            tin,
+            order,
+            up,
+        }
+    }
+
+    /// Number of nodes.
+    pub fn len(&self) -> usize {
+        self.par.len()
+    }
+
+    /// Returns true if the tree has no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.par.is_empty()
+    }
+
+    /// Root of the tree.
+    pub fn root(&self) -> usize {
+        self.root
+    }
+
+    /// Parent of `v`. `None` for the root.
+    pub fn parent(&self, v: usize) -> Option<usize> {
+        self.par[v]
+    }
+
+    /// Number of edges between the root and `v`.
+    pub fn depth(&self, v: usize) -> usize {
+        self.depth[v]
+    }
+
+    /// Number of nodes in the subtree of `v`.
+    pub fn subtree_size(&self, v: usize) -> usize {
+        self.size[v]
+    }
+
+    /// Time when DFS enters `v`, i.e. the position of `v` in the preorder.
+    pub fn tin(&self, v: usize) -> usize {
+        self.tin[v]
+    }
+
+    /// Time when DFS leaves `v`.
+    /// The subtree of `v` occupies `tin(v)..tout(v)` in the preorder.
+    pub fn tout(&self, v: usize) -> usize {
+        self.tin[v] + self.size[v]
+    }
+
+    /// Nodes in DFS preorder.
+    pub fn preorder(&self) -> &[usize] {
+        &self.order
+    }
+
+    /// Returns true if `u` is an ancestor of `v` (or `u == v`).
+    pub fn is_ancestor(&self, u: usize, v: usize) -> bool {
+        self.tin(u) <= self.tin(v) && self.tin(v) < self.tout(u)
+    }
+
+    /// O(log n).
+    /// `k`-th ancestor of `v`. `None` if `k > depth(v)`.
+    pub fn kth_ancestor(&self, v: usize, k: usize) -> Option<usize> {
+        if k > self.depth[v] {
+            return None;
+        }
+        let mut v = v;
+        for (i, up) in self.up.iter().enumerate() {
+            if (k >> i) & 1 != 0 {
+                v = up[v];
+            }
+        }
+        Some(v)
+    }
+
+    /// O(log n).
+    /// Lowest common ancestor of `u` and `v`.
+    pub fn lca(&self, u: usize, v: usize) -> usize {
+        let (mut u, mut v) = if self.depth[u] >= self.depth[v] {
+            (u, v)
+        } else {
+            (v, u)
+        };
+        u = self.kth_ancestor(u, self.depth[u] - self.depth[v]).unwrap();
+        if u == v {
+            return u;
+        }
+        for up in self.up.iter().rev() {
+            if up[u] != up[v] {
+                u = up[u];
+                v = up[v];
+            }
+        }
+        self.par[u].unwrap()
+    }
+
+    /// O(log n).
+    /// Number of edges between `u` and `v`.
+    pub fn dist(&self, u: usize, v: usize) -> usize {
+        self.depth[u] + self.depth[v] - 2 * self.depth[self.lca(u, v)]
+    }
+
+    /// O(log n + length).
+    /// Nodes on the path from `u` to `v`, both inclusive.
+    pub fn path(&self, u: usize, v: usize) -> Vec<usize> {
+        let w = self.lca(u, v);
+        let mut ret = vec![];
+        let mut cur = u;
+        while cur != w {
+            ret.push(cur);
+            cur = self.par[cur].unwrap();
+        }
+        ret.push(w);
+
+        let mut rest = vec![];
+        let mut cur = v;
+        while cur != w {
+            rest.push(cur);
+            cur = self.par[cur].unwrap();
+        }
+        ret.extend(rest.into_iter().rev());
+        ret
+    }
+}
+
+// Iterative DFS from `root`. Returns parents, depths and the preorder.
+// Panics if `g` is not a tree.
+pub(crate) fn traverse(
+    g: &[Vec<usize>],
+    root: usize,
+) -> (Vec<Option<usize>>, Vec<usize>, Vec<usize>) {
+    let n = g.len();
+    assert!(root < n, "root {} is out of range for {} nodes", root, n);
+    let mut par = vec![None; n];
+    let mut depth = vec![0; n];
+    let mut visited = vec![false; n];
+    let mut order = Vec::with_capacity(n);
+
+    visited[root] = true;
+    let mut stack = vec![root];
+    while let Some(u) = stack.pop() {
+        order.push(u);
+        for &v in g[u].iter() {
+            if Some(v) != par[u] {
+                // reaching a visited node other than the parent means a cycle
+                assert!(!visited[v], "graph is not a tree");
+                visited[v] = true;
+                par[v] = Some(u);
+                depth[v] = depth[u] + 1;
+                stack.push(v);
+            }
+        }
+    }
+    assert_eq!(order.len(), n, "graph is not a tree");
+
+    (par, depth, order)
+}
+
+/// Rerooting DP
+///
+/// Computes a tree DP for every node as the root in O(n).
+/// The value of a subtree rooted at `v` is `add_root(mconcat(values of child subtrees), v)`.
+/// `T` must be a commutative monoid.
+///
+/// Returns `add_root(mconcat(values of all adjacent subtrees), v)` for each node `v`.
+///
+/// # Examples
+///
+/// ```
+/// # use competitive::monoid::Max;
+/// # use competitive::tree::rerooting;
+/// // Farthest distance from each node
+/// let g = vec![vec![1], vec![0, 2, 3], vec![1], vec![1, 4], vec![3]];
+/// let dp = rerooting(&g, |x: &Max<usize>, _| Max(x.0 + 1));
+/// let dist = dp.iter().map(|x| x.0 - 1).collect::<Vec<_>>();
+/// assert_eq!(dist, vec![3, 2, 3, 2, 3]);
+/// ```
+///
+pub fn rerooting<T: Clone + Monoid>(g: &[Vec<usize>], add_root: impl Fn(&T, usize) -> T) -> Vec<T> {
+    let n = g.len();
+    if n == 0 {
+        return vec![];
+    }
+
+    let (par, _, order) = traverse(g, 0);
+    let par = par
+        .into_iter()
+        .map(|p| p.unwrap_or(usize::MAX))
+        .collect::<Vec<_>>();
+
+    // value of the subtree of `v` rooted at 0
+    let mut down = vec![T::mempty(); n];
+    for &u in order.iter().rev() {
+        let mut acc = T::mempty();
+        for &v in g[u].iter() {
+            if v != par[u] {
+                acc = T::mappend(&acc, &down[v]);
+            }
+        }
+        down[u] = add_root(&acc, u);
+    }
+
+    // value of the subtree of `par[v]` when `v` is the root
+    let mut up = vec![T::mempty(); n];
+    let mut ret = vec![T::mempty(); n];
+    for &u in order.iter() {
+        let vals = g[u]
+            .iter()
+            .map(|&v| {
+                if v == par[u] {
+                    up[u].clone()
+                } else {
+                    down[v].clone()
+                }
+            })
+            .collect::<Vec<_>>();
+
+        // suffix[i] = mconcat(vals[i..])
+        let mut suffix = vec![T::mempty(); vals.len() + 1];
+        for i in (0..vals.len()).rev() {
+            suffix[i] = T::mappend(&vals[i], &suffix[i + 1]);
+        }
+        ret[u] = add_root(&suffix[0], u);
+
+        let mut prefix = T::mempty();
+        for (i, &v) in g[u].iter().enumerate() {
+            if v != par[u] {
+                up[v] = add_root(&T::mappend(&prefix, &suffix[i + 1]), u);
+            }
+            prefix = T::mappend(&prefix, &vals[i]);
+        }
+    }
+
+    ret
+}
+
+/// Centroid decomposition
+///
+/// Returns the parent of each node in the centroid tree (`None` for the top centroid of each
+/// component), and the level of each node, i.e. its depth in the centroid tree.
+/// Every component at level `l` has at most `n >> l` nodes, so levels are less than `log2(n) + 1`.
+/// O(n log n).
+///
+/// # Examples
+///
+/// ```
+/// # use competitive::tree::centroid_decomposition;
+/// // 0 - 1 - 2 - 3 - 4
+/// let g = vec![vec![1], vec![0, 2], vec![1, 3], vec![2, 4], vec![3]];
+/// let (par, level) = centroid_decomposition(&g);
+/// assert_eq!(par[2], None);
+/// assert_eq!(level, vec![2, 1, 0, 1, 2]);
+/// ```
+///
+pub fn centroid_decomposition(g: &[Vec<usize>]) -> (Vec<Option<usize>>, Vec<usize>) {
+    let mut par = vec![None; g.len()];
+    let mut level = vec![0; g.len()];
+    visit_centroids(g, |c, p, l, _| {
+        par[c] = p;
+        level[c] = l;
+    });
+    (par, level)
+}
+
+/// Visit each centroid from the top of the centroid tree
+///
+/// `f(c, dead)` is called for each centroid `c`.
+/// `dead` marks the centroids visited before, and the nodes reachable from `c`
+/// without passing dead nodes are the component whose centroid is `c`.
+/// O(n log n) plus the time spent in `f`.
+/// It is O(n log n) in total if `f` only scans the component of `c`.
+///
+/// # Examples
+///
+/// ```
+/// # use competitive::tree::for_each_centroid;
+/// // sum of sizes of all components
+/// let g = vec![vec![1], vec![0, 2], vec![1, 3], vec![2, 4], vec![3]];
+/// let mut sum = 0;
+/// for_each_centroid(&g, |c, dead| {
+///     let mut stack = vec![(c, c)];
+///     while let Some((u, p)) = stack.pop() {
+///         sum += 1;
+///         for &v in g[u].iter() {
+///             if v != p && !dead[v] {
+///                 stack.push((v, u));
+///             }
+///         }
+///     }
+/// });
+/// assert_eq!(sum, 5 + 2 + 2 + 1 + 1);
+/// ```
+///
+pub fn for_each_centroid(g: &[Vec<usize>], mut f: impl FnMut(usize, &[bool])) {
+    visit_centroids(g, |c, _, _, dead| f(c, dead));
+}
+
+// Calls `f(centroid, parent centroid, level, dead)` in BFS order of the centroid tree.
+fn visit_centroids(g: &[Vec<usize>], mut f: impl FnMut(usize, Option<usize>, usize, &[bool])) {
+    let n = g.len();
+    let mut dead = vec![false; n];
+    let mut size = vec![0; n];
+    let mut par = vec![usize::MAX; n];
+    // id of the last component search which visited the node
+    let mut visited = vec![usize::MAX; n];
+    let mut searches = 0;
+    let mut order = vec![];
+    let mut q = VecDeque::new();
+    for root in 0..n {
+        if visited[root] != usize::MAX {
+            continue;
+        }
+        q.push_back((root, None, 0));
+        while let Some((root, p, l)) = q.pop_front() {
+            // sizes of subtrees in the component rooted at `root`
+            order.clear();
+            par[root] = usize::MAX;
+            visited[root] = searches;
+            let mut stack = vec![root];
+            while let Some(u) = stack.pop() {
+                order.push(u);
+                for &v in g[u].iter() {
+                    if v != par[u] && !dead[v] {
+                        // reaching a visited node other than the parent means a cycle
+                        assert_ne!(visited[v], searches, "graph is not a tree");
+                        visited[v] = searches;
+                        par[v] = u;
+                        stack.push(v);
+                    }
+                }
+            }
+            searches += 1;
+            for &u in order.iter().rev() {
+                size[u] = 1;
+                for &v in g[u].iter() {
+                    if v != par[u] && !dead[v] {
+                        size[u] += size[v];
+                    }
+                }
+            }
+
+            // go down to the heavy child while it has more than half of nodes
+            let total = order.len();
+            let mut c = root;
+            while let Some(&v) = g[c]
+                .iter()
+                .find(|&&v| v != par[c] && !dead[v] && size[v] * 2 > total)
+            {
+                c = v;
+            }
+
+            f(c, p, l, &dead);
+            dead[c] = true;
+            for &v in g[c].iter() {
+                if !dead[v] {
+                    q.push_back((v, Some(c), l + 1));
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_tree() {
+    use crate::graph::make_undirected_graph;
+
+    //       0
+    //     / | \
+    //    1  2  3
+    //   / \     \
+    //  4   5     6
+    //      |
+    //      7
+    let g = make_undirected_graph(8, &[(0, 1), (0, 2), (0, 3), (1, 4), (1, 5), (3, 6), (5, 7)]);
+    let t = Tree::new(&g, 0);
+
+    assert_eq!(t.len(), 8);
+    assert_eq!(t.root(), 0);
+    assert_eq!(t.parent(0), None);
+    assert_eq!(t.parent(7), Some(5));
+    assert_eq!(t.depth(7), 3);
+    assert_eq!(t.subtree_size(1), 4);
+    assert_eq!(t.subtree_size(0), 8);
+    for v in 0..8 {
+        let sub = &t.preorder()[t.tin(v)..t.tout(v)];
+        assert_eq!(sub[0], v);
+        assert!(sub.iter().all(|&u| t.is_ancestor(v, u)));
+    }
+
+    assert_eq!(t.lca(4, 7), 1);
+    assert_eq!(t.lca(7, 6), 0);
+    assert_eq!(t.lca(5, 7), 5);
+    assert_eq!(t.lca(2, 2), 2);
+    assert_eq!(t.kth_ancestor(7, 2), Some(1));
+    assert_eq!(t.kth_ancestor(7, 3), Some(0));
+    assert_eq!(t.kth_ancestor(7, 4), None);
+    assert_eq!(t.dist(4, 6), 4);
+    assert_eq!(t.path(7, 6), vec![7, 5, 1, 0, 3, 6]);
+    assert_eq!(t.path(1, 7), vec![1, 5, 7]);
+    assert_eq!(t.path(2, 2), vec![2]);
+
+    // a deep path must not overflow the stack
+    let n = 200_000;
+    let edges = (1..n).map(|i| (i - 1, i)).collect::<Vec<_>>();
+    let t = Tree::new(&make_undirected_graph(n, &edges), 0);
+    assert_eq!(t.depth(n - 1), n - 1);
+    assert_eq!(t.lca(n - 1, n / 2), n / 2);
+    assert_eq!(t.kth_ancestor(n - 1, n - 1), Some(0));
+    assert_eq!(t.dist(3, n - 2), n - 5);
+}
+
+#[test]
+#[should_panic(expected = "graph is not a tree")]
+fn test_tree_with_cycle() {
+    use crate::graph::make_undirected_graph;
+    Tree::new(&make_undirected_graph(3, &[(0, 1), (1, 2), (2, 0)]), 0);
+}
+
+#[test]
+#[should_panic(expected = "graph is not a tree")]
+fn test_tree_disconnected() {
+    use crate::graph::make_undirected_graph;
+    Tree::new(&make_undirected_graph(4, &[(0, 1), (2, 3)]), 0);
+}
+
+#[test]
+#[should_panic(expected = "out of range")]
+fn test_tree_empty() {
+    Tree::new(&[], 0);
+}
+
+#[test]
+#[should_panic(expected = "graph is not a tree")]
+fn test_rerooting_with_cycle() {
+    use crate::graph::make_undirected_graph;
+    use crate::monoid::Max;
+    let g = make_undirected_graph(3, &[(0, 1), (1, 2), (2, 0)]);
+    rerooting(&g, |x: &Max<usize>, _| Max(x.0 + 1));
+}
+
+#[test]
+#[should_panic(expected = "graph is not a tree")]
+fn test_rerooting_disconnected() {
+    use crate::graph::make_undirected_graph;
+    use crate::monoid::Max;
+    let g = make_undirected_graph(4, &[(0, 1), (2, 3)]);
+    rerooting(&g, |x: &Max<usize>, _| Max(x.0 + 1));
+}
+
+#[test]
+fn test_rerooting() {
+    use crate::graph::{make_dist_table, make_undirected_graph};
+    use crate::monoid::{Max, Product};
+
+    let g = make_undirected_graph(
+        9,
+        &[
+            (0, 1),
+            (1, 2),
+            (1, 3),
+            (3, 4),
+            (4, 5),
+            (4, 6),
+            (0, 7),
+            (7, 8),
+        ],
+    );
+
+    let dp = rerooting(&g, |x: &Max<usize>, _| Max(x.0 + 1));
+    for (v, x) in dp.iter().enumerate() {
+        let far = make_dist_table(&g, v).into_iter().max().unwrap().unwrap();
+        assert_eq!(x.0 - 1, far);
+    }
+
+    // number of connected subgraphs which contain each node
+    let dp = rerooting(&g, |x: &Product<u64>, _| Product(x.0 + 1));
+    for (v, x) in dp.iter().enumerate() {
+        let mut count = 0;
+        for mask in 0..1_u32 << g.len() {
+            if mask >> v & 1 == 0 {
+                continue;
+            }
+            let mut seen = 1_u32 << v;
+            let mut stack = vec![v];
+            while let Some(u) = stack.pop() {
+                for &w in g[u].iter() {
+                    if mask >> w & 1 != 0 && seen >> w & 1 == 0 {
+                        seen |= 1 << w;
+                        stack.push(w);
+                    }
+                }
+            }
+            if seen == mask {
+                count += 1;
+            }
+        }
+        assert_eq!(x.0 - 1, count);
+    }
+}
+
+#[test]
+#[should_panic(expected = "graph is not a tree")]
+fn test_centroid_decomposition_with_cycle() {
+    use crate::graph::make_undirected_graph;
+    centroid_decomposition(&make_undirected_graph(3, &[(0, 1), (1, 2), (2, 0)]));
+}
+
+#[test]
+fn test_centroid_decomposition() {
+    use crate::graph::{make_dist_table, make_undirected_graph};
+    use crate::test_util::rng;
+
+    let mut seed = 1_u64;
+    let mut rand = |m: usize| rng(&mut seed) as usize % m;
+    for _ in 0..50 {
+        let n = rand(60) + 1;
+        let edges = (1..n).map(|i| (rand(i), i)).collect::<Vec<_>>();
+        let g = make_undirected_graph(n, &edges);
+
+        let (par, level) = centroid_decomposition(&g);
+        assert_eq!(par.iter().filter(|p| p.is_none()).count(), 1);
+        for v in 0..n {
+            assert!(n >> level[v] >= 1);
+            if let Some(p) = par[v] {
+                assert_eq!(level[v], level[p] + 1);
+            }
+        }
+
+        // number of paths of length k for each k
+        let mut count = vec![0_u64; n];
+        for_each_centroid(&g, |c, dead| {
+            // cnt[d]: nodes at distance d from `c` in the component
+            let mut cnt = vec![1_u64];
+            for &s in g[c].iter() {
+                if dead[s] {
+                    continue;
+                }
+                let mut sub = vec![];
+                let mut stack = vec![(s, c, 1)];
+                while let Some((u, p, d)) = stack.pop() {
+                    if sub.len() <= d {
+                        sub.resize(d + 1, 0);
+                    }
+                    sub[d] += 1;
+                    for &v in g[u].iter() {
+                        if v != p && !dead[v] {
+                            stack.push((v, u, d + 1));
+                        }
+                    }
+                }
+                for (i, &x) in cnt.iter().enumerate() {
+                    for (j, &y) in sub.iter().enumerate() {
+                        if i + j < n {
+                            count[i + j] += x * y;
+                        }
+                    }
+                }
+                if cnt.len() < sub.len() {
+                    cnt.resize(sub.len(), 0);
+                }
+                for (i, &y) in sub.iter().enumerate() {
+                    cnt[i] += y;
+                }
+            }
+        });
+
+        let mut expected = vec![0_u64; n];
+        for u in 0..n {
+            for (v, d) in make_dist_table(&g, u).into_iter().enumerate() {
+                if u < v {
+                    expected[d.unwrap()] += 1;
+                }
+            }
+        }
+        assert_eq!(count[1..], expected[1..]);
+    }
+
+    // a deep path must not overflow the stack
+    let n = (1 << 17) - 1;
+    let edges = (1..n).map(|i| (i - 1, i)).collect::<Vec<_>>();
+    let (_, level) = centroid_decomposition(&make_undirected_graph(n, &edges));
+    assert_eq!(level.into_iter().max(), Some(16));
+}
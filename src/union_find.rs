@@ -1,33 +1,378 @@
-pub struct UnionFind(Vec<usize>);
+use num::Zero;
+use std::collections::HashMap;
+use std::ops::{Add, Sub};
+
+/// Disjoint set union with union by size and path compression
+pub struct UnionFind {
+    par: Vec<usize>,
+    size: Vec<usize>,
+    count: usize,
+}
 
 impl UnionFind {
     pub fn new(n: usize) -> UnionFind {
-        UnionFind((0..n).collect())
+        UnionFind {
+            par: (0..n).collect(),
+            size: vec![1; n],
+            count: n,
+        }
+    }
+
+    /// Returns the representative of the set containing `i`.
+    pub fn find(&mut self, i: usize) -> usize {
+        let mut root = i;
+        while self.par[root] != root {
+            root = self.par[root];
+        }
+
+        let mut cur = i;
+        while self.par[cur] != root {
+            let next = self.par[cur];
+            self.par[cur] = root;
+            cur = next;
+        }
+
+        root
+    }
+
+    /// Merges the sets containing `i` and `j`.
+    /// Returns `false` if they were already in the same set.
+    pub fn union(&mut self, i: usize, j: usize) -> bool {
+        let mut ni = self.find(i);
+        let mut nj = self.find(j);
+        if ni == nj {
+            return false;
+        }
+        if self.size[ni] < self.size[nj] {
+            std::mem::swap(&mut ni, &mut nj);
+        }
+        self.par[nj] = ni;
+        self.size[ni] += self.size[nj];
+        self.count -= 1;
+        true
+    }
+
+    /// Returns true if `i` and `j` belong to the same set.
+    pub fn same(&mut self, i: usize, j: usize) -> bool {
+        self.find(i) == self.find(j)
+    }
+
+    /// Size of the set containing `i`.
+    pub fn size(&mut self, i: usize) -> usize {
+        let r = self.find(i);
+        self.size[r]
+    }
+
+    /// Number of sets.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Returns members of each set.
+    /// Sets are ordered by their smallest member, and members are sorted.
+    pub fn groups(&mut self) -> Vec<Vec<usize>> {
+        let n = self.par.len();
+        let mut id = vec![usize::MAX; n];
+        let mut ret: Vec<Vec<usize>> = vec![];
+        for i in 0..n {
+            let r = self.find(i);
+            if id[r] == usize::MAX {
+                id[r] = ret.len();
+                ret.push(vec![]);
+            }
+            ret[id[r]].push(i);
+        }
+        ret
+    }
+}
+
+/// Union-find which maintains differences of potentials between members
+///
+/// `G` must be an abelian group, e.g. integers or `gf::GF<P>`.
+pub struct WeightedUnionFind<G> {
+    par: Vec<usize>,
+    size: Vec<usize>,
+    // potential relative to the parent
+    pot: Vec<G>,
+}
+
+impl<G: Copy + PartialEq + Zero + Add<Output = G> + Sub<Output = G>> WeightedUnionFind<G> {
+    pub fn new(n: usize) -> Self {
+        Self {
+            par: (0..n).collect(),
+            size: vec![1; n],
+            pot: vec![G::zero(); n],
+        }
     }
 
+    /// Returns the representative of the set containing `i`.
     pub fn find(&mut self, i: usize) -> usize {
-        if self.0[i] == i {
-            i
+        let mut root = i;
+        let mut sum = G::zero();
+        while self.par[root] != root {
+            sum = sum + self.pot[root];
+            root = self.par[root];
+        }
+
+        // `sum` is the potential of `cur` relative to the root
+        let mut cur = i;
+        while cur != root {
+            let next = self.par[cur];
+            let p = self.pot[cur];
+            self.pot[cur] = sum;
+            self.par[cur] = root;
+            sum = sum - p;
+            cur = next;
+        }
+
+        root
+    }
+
+    /// Potential of `i` relative to its representative.
+    pub fn weight(&mut self, i: usize) -> G {
+        self.find(i);
+        self.pot[i]
+    }
+
+    /// Adds the constraint `x[b] - x[a] = w`.
+    ///
+    /// Returns `Ok(false)` if the constraint is already implied,
+    /// and `Err(d)` if it contradicts the existing `x[b] - x[a] = d`.
+    pub fn union(&mut self, a: usize, b: usize, w: G) -> Result<bool, G> {
+        let ra = self.find(a);
+        let rb = self.find(b);
+        let d = w + self.pot[a] - self.pot[b];
+        if ra == rb {
+            return if d == G::zero() {
+                Ok(false)
+            } else {
+                Err(self.pot[b] - self.pot[a])
+            };
+        }
+
+        // now `x[rb] - x[ra] = d`
+        if self.size[ra] < self.size[rb] {
+            self.par[ra] = rb;
+            self.size[rb] += self.size[ra];
+            self.pot[ra] = G::zero() - d;
+        } else {
+            self.par[rb] = ra;
+            self.size[ra] += self.size[rb];
+            self.pot[rb] = d;
+        }
+        Ok(true)
+    }
+
+    /// Returns true if `a` and `b` belong to the same set.
+    pub fn same(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Returns `x[b] - x[a]` if it is determined.
+    pub fn diff(&mut self, a: usize, b: usize) -> Option<G> {
+        if self.same(a, b) {
+            Some(self.pot[b] - self.pot[a])
         } else {
-            let p = self.0[i];
-            self.0[i] = self.find(p);
-            self.0[i]
+            None
+        }
+    }
+
+    /// Size of the set containing `i`.
+    pub fn size(&mut self, i: usize) -> usize {
+        let r = self.find(i);
+        self.size[r]
+    }
+}
+
+/// Union-find which can undo unions
+///
+/// No path compression is done, so `find` takes O(log n).
+pub struct RollbackUnionFind {
+    par: Vec<usize>,
+    size: Vec<usize>,
+    count: usize,
+    // roots which were attached to another root
+    history: Vec<usize>,
+}
+
+impl RollbackUnionFind {
+    pub fn new(n: usize) -> Self {
+        Self {
+            par: (0..n).collect(),
+            size: vec![1; n],
+            count: n,
+            history: vec![],
+        }
+    }
+
+    /// Returns the representative of the set containing `i`.
+    pub fn find(&self, i: usize) -> usize {
+        let mut cur = i;
+        while self.par[cur] != cur {
+            cur = self.par[cur];
         }
+        cur
     }
 
-    pub fn union(&mut self, i: usize, j: usize) {
-        let ni = self.find(i);
-        let nj = self.find(j);
-        self.0[ni] = nj;
+    /// Merges the sets containing `i` and `j`.
+    /// Returns `false` if they were already in the same set.
+    pub fn union(&mut self, i: usize, j: usize) -> bool {
+        let mut ni = self.find(i);
+        let mut nj = self.find(j);
+        if ni == nj {
+            return false;
+        }
+        if self.size[ni] < self.size[nj] {
+            std::mem::swap(&mut ni, &mut nj);
+        }
+        self.par[nj] = ni;
+        self.size[ni] += self.size[nj];
+        self.count -= 1;
+        self.history.push(nj);
+        true
+    }
+
+    /// Returns true if `i` and `j` belong to the same set.
+    pub fn same(&self, i: usize, j: usize) -> bool {
+        self.find(i) == self.find(j)
+    }
+
+    /// Size of the set containing `i`.
+    pub fn size(&self, i: usize) -> usize {
+        self.size[self.find(i)]
+    }
+
+    /// Number of sets.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Returns the current state which can be passed to `rollback`.
+    pub fn snapshot(&self) -> usize {
+        self.history.len()
+    }
+
+    /// Undo unions until the state becomes `snapshot`.
+    pub fn rollback(&mut self, snapshot: usize) {
+        while self.history.len() > snapshot {
+            self.undo();
+        }
+    }
+
+    /// Undo the last successful union.
+    pub fn undo(&mut self) {
+        let c = self.history.pop().unwrap();
+        let p = self.par[c];
+        self.par[c] = c;
+        self.size[p] -= self.size[c];
+        self.count += 1;
+    }
+}
+
+/// Query for `offline_dynamic_connectivity`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectivityQuery {
+    /// Add an edge
+    Add(usize, usize),
+    /// Remove an edge which was added before
+    Remove(usize, usize),
+    /// Ask whether two nodes are connected
+    Connected(usize, usize),
+}
+
+/// Answers connectivity queries on a graph with `n` nodes
+/// where edges are added and removed.
+///
+/// Returns answers of `Connected` queries in order.
+/// O(q log q log n).
+pub fn offline_dynamic_connectivity(n: usize, queries: &[ConnectivityQuery]) -> Vec<bool> {
+    use ConnectivityQuery::*;
+
+    let q = queries.len();
+    let size = q.next_power_of_two();
+
+    // edges alive in the time range covered by each node
+    let mut seg = vec![vec![]; size * 2];
+    let mut add_edge = |l: usize, r: usize, e: (usize, usize)| {
+        let mut l = l + size;
+        let mut r = r + size;
+        while l < r {
+            if l & 1 != 0 {
+                seg[l].push(e);
+                l += 1;
+            }
+            if r & 1 != 0 {
+                r -= 1;
+                seg[r].push(e);
+            }
+            l /= 2;
+            r /= 2;
+        }
+    };
+
+    let key = |u: usize, v: usize| (u.min(v), u.max(v));
+    let mut alive: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+    for (t, query) in queries.iter().enumerate() {
+        match *query {
+            Add(u, v) => alive.entry(key(u, v)).or_default().push(t),
+            Remove(u, v) => {
+                let s = alive
+                    .get_mut(&key(u, v))
+                    .and_then(|ts| ts.pop())
+                    .expect("remove an edge which does not exist");
+                add_edge(s, t, (u, v));
+            }
+            Connected(_, _) => {}
+        }
+    }
+    for (&e, ts) in alive.iter() {
+        for &s in ts.iter() {
+            add_edge(s, q, e);
+        }
+    }
+
+    // visit the node `k` which covers the time range `l..r`
+    fn dfs(
+        k: usize,
+        l: usize,
+        r: usize,
+        seg: &[Vec<(usize, usize)>],
+        queries: &[ConnectivityQuery],
+        uf: &mut RollbackUnionFind,
+        ans: &mut Vec<bool>,
+    ) {
+        if l >= queries.len() {
+            return;
+        }
+        let snapshot = uf.snapshot();
+        for &(u, v) in seg[k].iter() {
+            uf.union(u, v);
+        }
+        if r - l == 1 {
+            if let Connected(u, v) = queries[l] {
+                ans.push(uf.same(u, v));
+            }
+        } else {
+            let m = (l + r) / 2;
+            dfs(k * 2, l, m, seg, queries, uf, ans);
+            dfs(k * 2 + 1, m, r, seg, queries, uf, ans);
+        }
+        uf.rollback(snapshot);
     }
+
+    let mut uf = RollbackUnionFind::new(n);
+    let mut ans = vec![];
+    dfs(1, 0, size, &seg, queries, &mut uf, &mut ans);
+    ans
 }
 
 #[test]
 fn union_find_test() {
     let mut uf = UnionFind::new(5);
-    uf.union(0, 1);
-    uf.union(2, 3);
-    uf.union(0, 4);
+    assert!(uf.union(0, 1));
+    assert!(uf.union(2, 3));
+    assert!(uf.union(0, 4));
+    assert!(!uf.union(4, 1));
 
     assert_eq!(uf.find(0), uf.find(1));
     assert_eq!(uf.find(2), uf.find(3));
@@ -35,4 +380,113 @@ fn union_find_test() {
     assert_eq!(uf.find(1), uf.find(4));
     assert_ne!(uf.find(0), uf.find(2));
     assert_ne!(uf.find(3), uf.find(4));
+
+    assert!(uf.same(1, 4));
+    assert!(!uf.same(1, 3));
+    assert_eq!(uf.size(4), 3);
+    assert_eq!(uf.size(2), 2);
+    assert_eq!(uf.count(), 2);
+    assert_eq!(uf.groups(), vec![vec![0, 1, 4], vec![2, 3]]);
+}
+
+#[test]
+fn union_find_long_chain_test() {
+    let n = 1_000_000;
+    let mut uf = UnionFind::new(n);
+    for i in 1..n {
+        uf.union(i - 1, i);
+    }
+    assert_eq!(uf.count(), 1);
+    assert_eq!(uf.size(0), n);
+    assert!(uf.same(0, n - 1));
+}
+
+#[test]
+fn weighted_union_find_test() {
+    let mut uf = WeightedUnionFind::<i64>::new(5);
+    assert_eq!(uf.union(0, 1, 3), Ok(true));
+    assert_eq!(uf.union(1, 2, -5), Ok(true));
+    assert_eq!(uf.union(3, 4, 10), Ok(true));
+    assert_eq!(uf.diff(0, 2), Some(-2));
+    assert_eq!(uf.diff(2, 0), Some(2));
+    assert_eq!(uf.diff(0, 3), None);
+
+    assert_eq!(uf.union(4, 2, 1), Ok(true));
+    assert_eq!(uf.diff(0, 3), Some(-13));
+    assert_eq!(uf.union(3, 0, 13), Ok(false));
+    assert_eq!(uf.union(3, 0, 12), Err(13));
+    assert_eq!(uf.size(1), 5);
+
+    // merging pairs of equal size sets builds paths of length log n
+    let n = 1 << 10;
+    let x = |i: usize| (i * i) as i64;
+    let mut uf = WeightedUnionFind::<i64>::new(n);
+    let mut s = 1;
+    while s < n {
+        for i in (0..n).step_by(2 * s) {
+            assert_eq!(uf.union(i + s, i, x(i) - x(i + s)), Ok(true));
+        }
+        s *= 2;
+    }
+    for i in (0..n).rev() {
+        assert_eq!(uf.diff(i, 0), Some(x(0) - x(i)));
+        assert_eq!(uf.weight(i) - uf.weight(n - 1), x(i) - x(n - 1));
+    }
+
+    type GF = crate::gf::GF<7>;
+    let mut uf = WeightedUnionFind::<GF>::new(3);
+    assert_eq!(uf.union(0, 1, GF::new(5)), Ok(true));
+    assert_eq!(uf.union(1, 2, GF::new(4)), Ok(true));
+    assert_eq!(uf.diff(0, 2), Some(GF::new(2)));
+    assert_eq!(uf.union(2, 0, GF::new(5)), Ok(false));
+}
+
+#[test]
+fn rollback_union_find_test() {
+    let mut uf = RollbackUnionFind::new(4);
+    assert!(uf.union(0, 1));
+    let s = uf.snapshot();
+    assert!(uf.union(2, 3));
+    assert!(uf.union(1, 2));
+    assert!(!uf.union(0, 3));
+    assert_eq!(uf.count(), 1);
+    assert_eq!(uf.size(3), 4);
+
+    uf.undo();
+    assert!(!uf.same(0, 3));
+    assert!(uf.same(2, 3));
+    uf.rollback(s);
+    assert!(!uf.same(2, 3));
+    assert!(uf.same(0, 1));
+    assert_eq!(uf.count(), 3);
+    assert_eq!(uf.size(0), 2);
+}
+
+#[test]
+fn offline_dynamic_connectivity_test() {
+    use ConnectivityQuery::*;
+
+    let queries = [
+        Connected(0, 1),
+        Add(0, 1),
+        Add(1, 2),
+        Connected(0, 2),
+        Add(0, 2),
+        Remove(0, 1),
+        Connected(0, 1),
+        Remove(1, 2),
+        Connected(0, 1),
+        Connected(0, 2),
+        Add(1, 0),
+        Add(0, 1),
+        Remove(0, 1),
+        Connected(1, 2),
+        Remove(1, 0),
+        Connected(1, 2),
+        Connected(3, 3),
+    ];
+    assert_eq!(
+        offline_dynamic_connectivity(4, &queries),
+        vec![false, true, true, false, true, true, false, true]
+    );
 }